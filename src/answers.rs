@@ -0,0 +1,285 @@
+use crate::config::{self, escape};
+use crate::solution::{Answer, Part};
+use std::fmt;
+use std::path::PathBuf;
+
+pub const ANSWERS_DIR: &str = "answers";
+
+/// What is known about the answer to a single part.
+#[derive(Debug, Default, PartialEq)]
+struct PartAnswers {
+    correct: Option<String>,
+    /// Answers the puzzle server rejected.
+    rejected: Vec<String>,
+    /// The highest answer known to be too low.
+    too_low: Option<i64>,
+    /// The lowest answer known to be too high.
+    too_high: Option<i64>,
+}
+
+/// The expected answers for a single day, stored as `answers/puzzle_<day>.toml`.
+#[derive(Debug, Default, PartialEq)]
+pub struct Answers {
+    part_1: PartAnswers,
+    part_2: PartAnswers,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Rejection {
+    TooHigh,
+    TooLow,
+    Wrong,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum Verdict {
+    Pass,
+    Fail { expected: String },
+    Unknown,
+}
+
+impl fmt::Display for Verdict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Verdict::Pass => f.write_str("PASS"),
+            Verdict::Fail { expected } => write!(f, "FAIL, expected {}", expected),
+            Verdict::Unknown => f.write_str("UNKNOWN"),
+        }
+    }
+}
+
+impl Answers {
+    fn part(&self, part: Part) -> &PartAnswers {
+        match part {
+            Part::One => &self.part_1,
+            Part::Two => &self.part_2,
+        }
+    }
+
+    fn part_mut(&mut self, part: Part) -> &mut PartAnswers {
+        match part {
+            Part::One => &mut self.part_1,
+            Part::Two => &mut self.part_2,
+        }
+    }
+
+    pub fn get(&self, part: Part) -> Option<&str> {
+        self.part(part).correct.as_deref()
+    }
+
+    pub fn set(&mut self, part: Part, answer: String) {
+        self.part_mut(part).correct = Some(answer);
+    }
+
+    pub fn verify(&self, part: Part, answer: &Answer) -> Verdict {
+        match (self.get(part), answer) {
+            (_, Answer::NotImplemented) | (None, _) => Verdict::Unknown,
+            (Some(expected), answer) if expected == answer.to_string() => Verdict::Pass,
+            (Some(expected), _) => Verdict::Fail {
+                expected: expected.to_string(),
+            },
+        }
+    }
+
+    /// Records a wrong answer, narrowing the bounds when the server said which way it was off.
+    pub fn reject(&mut self, part: Part, answer: &Answer, rejection: Rejection) {
+        let part = self.part_mut(part);
+        let text = answer.to_string();
+        if !part.rejected.contains(&text) {
+            part.rejected.push(text);
+        }
+        let Answer::Integer(value) = *answer else {
+            return;
+        };
+        match rejection {
+            Rejection::TooHigh => {
+                part.too_high = Some(part.too_high.map_or(value, |high| high.min(value)))
+            }
+            Rejection::TooLow => {
+                part.too_low = Some(part.too_low.map_or(value, |low| low.max(value)))
+            }
+            Rejection::Wrong => {}
+        }
+    }
+
+    /// Explains why `answer` is known to be wrong without asking the puzzle server.
+    pub fn known_wrong(&self, part: Part, answer: &Answer) -> Option<String> {
+        let part = self.part(part);
+        if part.rejected.contains(&answer.to_string()) {
+            return Some(format!("{} was rejected before", answer));
+        }
+        let Answer::Integer(value) = *answer else {
+            return None;
+        };
+        if let Some(high) = part.too_high
+            && value >= high
+        {
+            return Some(format!("{} is too high, as {} already was", value, high));
+        }
+        if let Some(low) = part.too_low
+            && value <= low
+        {
+            return Some(format!("{} is too low, as {} already was", value, low));
+        }
+        None
+    }
+
+    fn parse(input: &str) -> Result<Self, String> {
+        let mut answers = Answers::default();
+        let invalid_line = |line_index: usize| {
+            format!(
+                "line {}: expected `part_<1|2>[_rejected|_too_low|_too_high] = <value>`",
+                line_index + 1
+            )
+        };
+        for (line_index, key, value) in config::parse_entries(input).map_err(invalid_line)? {
+            let (part, field) = if let Some(field) = key.strip_prefix("part_1") {
+                (&mut answers.part_1, field)
+            } else if let Some(field) = key.strip_prefix("part_2") {
+                (&mut answers.part_2, field)
+            } else {
+                return Err(invalid_line(line_index));
+            };
+            let parsed = match field {
+                "" => value.into_text().map(|text| part.correct = Some(text)),
+                "_rejected" => value.into_list().map(|list| part.rejected = list),
+                "_too_low" => value
+                    .into_text()
+                    .and_then(|text| text.parse().ok())
+                    .map(|low| part.too_low = Some(low)),
+                "_too_high" => value
+                    .into_text()
+                    .and_then(|text| text.parse().ok())
+                    .map(|high| part.too_high = Some(high)),
+                _ => None,
+            };
+            parsed.ok_or_else(|| invalid_line(line_index))?;
+        }
+        Ok(answers)
+    }
+
+    fn serialize(&self) -> String {
+        let mut contents = String::new();
+        for (key, part) in [("part_1", &self.part_1), ("part_2", &self.part_2)] {
+            if let Some(correct) = &part.correct {
+                contents.push_str(&format!("{} = \"{}\"\n", key, escape(correct)));
+            }
+            if !part.rejected.is_empty() {
+                let rejected = part
+                    .rejected
+                    .iter()
+                    .map(|answer| format!("\"{}\"", escape(answer)))
+                    .collect::<Vec<_>>();
+                contents.push_str(&format!("{}_rejected = [{}]\n", key, rejected.join(", ")));
+            }
+            if let Some(low) = part.too_low {
+                contents.push_str(&format!("{}_too_low = {}\n", key, low));
+            }
+            if let Some(high) = part.too_high {
+                contents.push_str(&format!("{}_too_high = {}\n", key, high));
+            }
+        }
+        contents
+    }
+}
+
+pub struct AnswerStore {
+    dir: PathBuf,
+}
+
+impl AnswerStore {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    fn path(&self, day: usize) -> PathBuf {
+        self.dir.join(format!("puzzle_{}.toml", day))
+    }
+
+    /// Loads the answers for `day`, which are empty when nothing has been recorded yet.
+    pub fn load(&self, day: usize) -> Result<Answers, String> {
+        let path = self.path(day);
+        match std::fs::read_to_string(&path) {
+            Ok(contents) => Answers::parse(&contents)
+                .map_err(|error| format!("invalid answers file `{}`: {}", path.display(), error)),
+            Err(error) if error.kind() == std::io::ErrorKind::NotFound => Ok(Answers::default()),
+            Err(error) => Err(format!("failed to read `{}`: {}", path.display(), error)),
+        }
+    }
+
+    pub fn save(&self, day: usize, answers: &Answers) -> Result<(), String> {
+        let path = self.path(day);
+        std::fs::create_dir_all(&self.dir)
+            .and_then(|_| std::fs::write(&path, answers.serialize()))
+            .map_err(|error| format!("failed to write `{}`: {}", path.display(), error))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_answers_round_trip() {
+        let mut answers = Answers::default();
+        answers.set(Part::One, "1031".to_string());
+        answers.set(Part::Two, "say \"hi\"".to_string());
+        assert_eq!(Answers::parse(&answers.serialize()), Ok(answers));
+    }
+
+    #[test]
+    fn test_answers_rejections() {
+        let mut answers = Answers::default();
+        answers.reject(Part::Two, &Answer::Integer(500), Rejection::TooHigh);
+        answers.reject(Part::Two, &Answer::Integer(400), Rejection::TooHigh);
+        answers.reject(Part::Two, &Answer::Integer(100), Rejection::TooLow);
+        answers.reject(Part::Two, &Answer::Integer(250), Rejection::Wrong);
+        assert_eq!(
+            answers.serialize(),
+            "part_2_rejected = [\"500\", \"400\", \"100\", \"250\"]\n\
+             part_2_too_low = 100\n\
+             part_2_too_high = 400\n"
+        );
+        let answers = Answers::parse(&answers.serialize()).unwrap();
+        assert!(
+            answers
+                .known_wrong(Part::Two, &Answer::Integer(250))
+                .is_some()
+        );
+        assert!(
+            answers
+                .known_wrong(Part::Two, &Answer::Integer(450))
+                .is_some()
+        );
+        assert!(
+            answers
+                .known_wrong(Part::Two, &Answer::Integer(50))
+                .is_some()
+        );
+        assert_eq!(answers.known_wrong(Part::Two, &Answer::Integer(300)), None);
+        assert_eq!(answers.known_wrong(Part::One, &Answer::Integer(250)), None);
+    }
+
+    #[test]
+    fn test_answers_verify() {
+        let answers = Answers::parse("# day 1\npart_1 = \"1031\"\n").unwrap();
+        assert_eq!(
+            answers.verify(Part::One, &Answer::Integer(1031)),
+            Verdict::Pass
+        );
+        assert_eq!(
+            answers.verify(Part::One, &Answer::Integer(1030)),
+            Verdict::Fail {
+                expected: "1031".to_string()
+            }
+        );
+        assert_eq!(
+            answers.verify(Part::Two, &Answer::Integer(5831)),
+            Verdict::Unknown
+        );
+        assert_eq!(
+            answers.verify(Part::One, &Answer::NotImplemented),
+            Verdict::Unknown
+        );
+    }
+}
@@ -0,0 +1,234 @@
+use crate::error::ParseError;
+use crate::solution::{Answer, Options, Part, SolvePuzzle};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::time::Duration;
+
+#[derive(Clone, Copy, Debug, Eq, Ord, PartialEq, PartialOrd)]
+pub enum Phase {
+    Parse,
+    Solve(Part),
+}
+
+impl Phase {
+    fn key(&self) -> &'static str {
+        match self {
+            Phase::Parse => "parse",
+            Phase::Solve(Part::One) => "part_1",
+            Phase::Solve(Part::Two) => "part_2",
+        }
+    }
+
+    fn from_key(key: &str) -> Option<Self> {
+        match key {
+            "parse" => Some(Phase::Parse),
+            "part_1" => Some(Phase::Solve(Part::One)),
+            "part_2" => Some(Phase::Solve(Part::Two)),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for Phase {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Phase::Parse => f.write_str("parse"),
+            Phase::Solve(part) => write!(f, "part {}", part),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct Stats {
+    pub min: Duration,
+    pub median: Duration,
+    pub mean: Duration,
+    pub stddev: Duration,
+}
+
+impl Stats {
+    fn new(mut samples: Vec<Duration>) -> Self {
+        samples.sort();
+        let count = samples.len() as u32;
+        let middle = samples.len() / 2;
+        let median = if samples.len().is_multiple_of(2) {
+            (samples[middle - 1] + samples[middle]) / 2
+        } else {
+            samples[middle]
+        };
+        let mean = samples.iter().sum::<Duration>() / count;
+        let variance = samples
+            .iter()
+            .map(|sample| (sample.as_secs_f64() - mean.as_secs_f64()).powi(2))
+            .sum::<f64>()
+            / count as f64;
+        Self {
+            min: samples[0],
+            median,
+            mean,
+            stddev: Duration::from_secs_f64(variance.sqrt()),
+        }
+    }
+}
+
+/// Solves the puzzle `iterations` times and summarises the time spent in each phase.
+pub fn bench_puzzle(
+    solve_puzzle: SolvePuzzle,
+    input: &str,
+    iterations: usize,
+) -> Result<Vec<(Phase, Stats)>, ParseError> {
+    let mut samples = BTreeMap::<Phase, Vec<Duration>>::new();
+    for _ in 0..iterations {
+        let report = solve_puzzle(input, &[Part::One, Part::Two], &Options::default())?;
+        samples
+            .entry(Phase::Parse)
+            .or_default()
+            .push(report.parse_elapsed);
+        for part in report.parts {
+            if part.answer == Answer::NotImplemented {
+                continue;
+            }
+            samples
+                .entry(Phase::Solve(part.part))
+                .or_default()
+                .push(part.elapsed);
+        }
+    }
+    Ok(samples
+        .into_iter()
+        .map(|(phase, samples)| (phase, Stats::new(samples)))
+        .collect())
+}
+
+/// Median timings per day and phase, saved so that later runs can be compared against them.
+#[derive(Debug, Default, PartialEq)]
+pub struct Baseline {
+    medians: BTreeMap<(usize, Phase), Duration>,
+}
+
+impl Baseline {
+    pub fn load(path: &str) -> Result<Self, String> {
+        let contents = std::fs::read_to_string(path)
+            .map_err(|error| format!("failed to read `{}`: {}", path, error))?;
+        Self::parse(&contents).map_err(|error| format!("invalid baseline `{}`: {}", path, error))
+    }
+
+    fn parse(input: &str) -> Result<Self, String> {
+        let mut baseline = Baseline::default();
+        for (line_index, line) in input.lines().enumerate() {
+            if line.trim().is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let fields = line.split_whitespace().collect::<Vec<_>>();
+            let entry = match fields[..] {
+                [day, phase, median_ns] => day
+                    .parse::<usize>()
+                    .ok()
+                    .zip(Phase::from_key(phase).zip(median_ns.parse::<u64>().ok())),
+                _ => None,
+            };
+            let (day, (phase, median_ns)) = entry.ok_or_else(|| {
+                format!(
+                    "line {}: expected `<day> <phase> <median in ns>`",
+                    line_index + 1
+                )
+            })?;
+            baseline
+                .medians
+                .insert((day, phase), Duration::from_nanos(median_ns));
+        }
+        Ok(baseline)
+    }
+
+    pub fn save(&self, path: &str) -> Result<(), String> {
+        let mut contents = String::from("# day phase median_ns\n");
+        for ((day, phase), median) in self.medians.iter() {
+            contents.push_str(&format!("{} {} {}\n", day, phase.key(), median.as_nanos()));
+        }
+        std::fs::write(path, contents)
+            .map_err(|error| format!("failed to write `{}`: {}", path, error))
+    }
+
+    pub fn add(&mut self, day: usize, stats: &[(Phase, Stats)]) {
+        for (phase, stats) in stats {
+            self.medians.insert((day, *phase), stats.median);
+        }
+    }
+
+    fn get(&self, day: usize, phase: Phase) -> Option<Duration> {
+        self.medians.get(&(day, phase)).copied()
+    }
+}
+
+fn format_duration(duration: Duration) -> String {
+    format!("{:.2?}", duration)
+}
+
+/// Prints the statistics of a day, returning whether none of its phases regressed by more than
+/// `threshold` percent compared to `baseline`.
+pub fn print_stats(
+    day: usize,
+    stats: &[(Phase, Stats)],
+    baseline: Option<&Baseline>,
+    threshold: f64,
+) -> bool {
+    println!(
+        "Day {:<6} {:>12} {:>12} {:>12} {:>12}",
+        day, "min", "median", "mean", "stddev"
+    );
+    let mut passed = true;
+    for (phase, stats) in stats {
+        let comparison = match baseline.and_then(|baseline| baseline.get(day, *phase)) {
+            Some(previous) if !previous.is_zero() => {
+                let change = (stats.median.as_secs_f64() / previous.as_secs_f64() - 1.0) * 100.0;
+                let regressed = change > threshold;
+                passed &= !regressed;
+                format!(
+                    "  {:+.1}% vs baseline{}",
+                    change,
+                    if regressed { " REGRESSION" } else { "" }
+                )
+            }
+            _ => String::new(),
+        };
+        println!(
+            "  {:<8} {:>12} {:>12} {:>12} {:>12}{}",
+            phase.to_string(),
+            format_duration(stats.min),
+            format_duration(stats.median),
+            format_duration(stats.mean),
+            format_duration(stats.stddev),
+            comparison
+        );
+    }
+    passed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stats() {
+        let samples = [4, 1, 3, 2].map(Duration::from_millis).to_vec();
+        let stats = Stats::new(samples);
+        assert_eq!(stats.min, Duration::from_millis(1));
+        assert_eq!(stats.median, Duration::from_micros(2500));
+        assert_eq!(stats.mean, Duration::from_micros(2500));
+        assert!(stats.stddev.as_nanos().abs_diff(1_118_034) <= 1);
+    }
+
+    #[test]
+    fn test_baseline_round_trip() {
+        let mut baseline = Baseline::default();
+        baseline.add(
+            3,
+            &[(
+                Phase::Solve(Part::Two),
+                Stats::new(vec![Duration::from_nanos(1500)]),
+            )],
+        );
+        let contents = "# day phase median_ns\n3 part_2 1500\n";
+        assert_eq!(Baseline::parse(contents), Ok(baseline));
+    }
+}
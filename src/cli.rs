@@ -0,0 +1,683 @@
+use crate::output::Format;
+use crate::puzzle_1::TraceFormat;
+use crate::puzzle_8::TreeFormat;
+use crate::solution::{Options, Part};
+use std::fmt;
+use std::str::FromStr;
+
+pub const USAGE: &str = "\
+Usage: advent-of-code-2025 <command> [options]
+
+Commands:
+  run <day> [--part <1|2>] [--input <path>] [--record]  Run a single puzzle
+      [--format <text|json|csv>] [--set <key>=<value>]...
+  run --all [--part <1|2>] [--record] [--jobs <n>]      Run every puzzle and print a summary
+      [--format <text|json|csv>]
+  bench <day|--all> [--iterations <n>] [--save <path>]   Benchmark parsing and both parts
+        [--baseline <path>] [--threshold <percent>]
+  fetch <day|--all> [--base-url <url>]                   Download puzzle inputs that are missing
+  submit <day> <1|2> [--base-url <url>]                  Submit the answer to a part
+  new <day> [--title <title>]                            Create and register a module for a new day
+  mst [--input <path>] [--format <csv|dot>]              Write the minimum spanning tree of day 8
+      [--set <key>=<value>]... [--output <path>]
+  trace [--input <path>] [--format <csv|table>]          Write the dial position after every line
+        [--set <key>=<value>]... [--output <path>]       of day 1
+  scan [--input <path>] [--part <1|2>] [--list]          Write the invalid IDs of each day 2 range
+       [--set <key>=<value>]... [--output <path>]
+  help                                                   Show this message
+
+Options:
+  --part <1|2>             Only run the given part (both parts run by default)
+  --input <path|->         Read the puzzle input from <path>, or stdin for `-`, instead of
+                           inputs/puzzle_<day>.txt; gzip-compressed inputs are decompressed
+  --set <key>=<value>      Change a setting of the day, see below; `trace`, `scan` and
+                           `mst` take those of days 1, 2 and 8
+  --record                 Store answers that are not yet in answers/puzzle_<day>.toml
+  --jobs <n>               Number of days run in parallel by `run --all` (default: 1)
+  --format <text|json|csv> Print one record per day and part instead of text (default: text)
+  --iterations <n>         Number of times each phase is measured (default: 10)
+  --save <path>            Save the benchmark results as a baseline
+  --baseline <path>        Compare the benchmark results against a saved baseline
+  --threshold <percent>    Slowdown of the median that counts as a regression (default: 10)
+  --title <title>          Title of the new day's puzzle (default: TODO)
+  --base-url <url>         Puzzle server to use instead of the configured one
+  --list                   List the invalid IDs found by `scan`, not just their count and sum
+  --output <path>          Write to <path> instead of stdout
+
+Answers are checked against answers/puzzle_<day>.toml and reported as PASS, FAIL or
+UNKNOWN. Any FAIL results in a non-zero exit status.
+
+Settings:
+  day 1  size=<n>                                Positions on the dial (default: 100)
+         start=<position>                        Starting position (default: half the size)
+         targets=<position>,...                  Positions whose hits are counted (default: 0)
+  day 2  repetitions=<n|n+|n-m|n,m,...>          Times a block is repeated in an invalid ID
+                                                 (default: 2 in part 1, 2+ in part 2)
+         min_block=<n>                           Digits in the repeated block (default: 1)
+  day 8  metric=<euclidean|manhattan|chebyshev>  Distance between junction boxes (default:
+                                                 euclidean, squared)
+         connections=<n>                         Closest pairs connected in part 1 (default:
+                                                 the number of junction boxes)";
+
+#[derive(Debug, PartialEq)]
+pub enum Selection {
+    Day(usize),
+    All,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct RunArgs {
+    pub selection: Selection,
+    pub parts: Vec<Part>,
+    pub input: Option<String>,
+    pub record: bool,
+    pub jobs: usize,
+    pub format: Format,
+    pub options: Options,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct BenchArgs {
+    pub selection: Selection,
+    pub iterations: usize,
+    pub save: Option<String>,
+    pub baseline: Option<String>,
+    pub threshold: f64,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct FetchArgs {
+    pub selection: Selection,
+    pub base_url: Option<String>,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct SubmitArgs {
+    pub day: usize,
+    pub part: Part,
+    pub base_url: Option<String>,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct NewArgs {
+    pub day: usize,
+    pub title: String,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct MstArgs {
+    pub input: Option<String>,
+    pub options: Options,
+    pub format: TreeFormat,
+    pub output: Option<String>,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct TraceArgs {
+    pub input: Option<String>,
+    pub options: Options,
+    pub format: TraceFormat,
+    pub output: Option<String>,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct ScanArgs {
+    pub input: Option<String>,
+    pub part: Part,
+    pub options: Options,
+    pub list: bool,
+    pub output: Option<String>,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum Command {
+    Run(RunArgs),
+    Bench(BenchArgs),
+    Fetch(FetchArgs),
+    Submit(SubmitArgs),
+    New(NewArgs),
+    Mst(MstArgs),
+    Trace(TraceArgs),
+    Scan(ScanArgs),
+    Help,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct CliError(String);
+
+impl fmt::Display for CliError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+fn parse_day(value: &str, days: &[usize]) -> Result<usize, CliError> {
+    match value.parse::<usize>() {
+        Ok(day) if days.contains(&day) => Ok(day),
+        Ok(day) => Err(CliError(format!("no puzzle registered for day {}", day))),
+        Err(_) => Err(CliError(format!(
+            "invalid day `{}`, expected a number",
+            value
+        ))),
+    }
+}
+
+fn parse_part(value: &str) -> Result<Part, CliError> {
+    match value {
+        "1" => Ok(Part::One),
+        "2" => Ok(Part::Two),
+        _ => Err(CliError(format!(
+            "invalid part `{}`, expected 1 or 2",
+            value
+        ))),
+    }
+}
+
+fn expect_value(flag: &str, value: Option<String>) -> Result<String, CliError> {
+    value.ok_or_else(|| CliError(format!("missing value for `{}`", flag)))
+}
+
+fn parse_value<T: FromStr>(flag: &str, value: Option<String>) -> Result<T, CliError> {
+    let value = expect_value(flag, value)?;
+    value
+        .parse::<T>()
+        .map_err(|_| CliError(format!("invalid value `{}` for `{}`", value, flag)))
+}
+
+fn parse_setting(options: &mut Options, value: Option<String>) -> Result<(), CliError> {
+    let value = expect_value("--set", value)?;
+    let (key, setting) = value.split_once('=').ok_or_else(|| {
+        CliError(format!(
+            "invalid value `{}` for `--set`, expected `<key>=<value>`",
+            value
+        ))
+    })?;
+    options.set(key.trim(), setting.trim());
+    Ok(())
+}
+
+fn parse_run_args(
+    mut args: impl Iterator<Item = String>,
+    days: &[usize],
+) -> Result<RunArgs, CliError> {
+    let mut selection = None;
+    let mut part = None;
+    let mut input = None;
+    let mut record = false;
+    let mut jobs = 1;
+    let mut format = Format::Text;
+    let mut options = Options::default();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--all" => selection = Some(Selection::All),
+            "--set" => parse_setting(&mut options, args.next())?,
+            "--part" => part = Some(parse_part(&expect_value(&arg, args.next())?)?),
+            "--input" => input = Some(expect_value(&arg, args.next())?),
+            "--record" => record = true,
+            "--jobs" => jobs = parse_value(&arg, args.next())?,
+            "--format" => format = parse_value(&arg, args.next())?,
+            flag if flag.starts_with("--") => {
+                return Err(CliError(format!("unknown option `{}`", flag)));
+            }
+            day if selection.is_none() => selection = Some(Selection::Day(parse_day(day, days)?)),
+            extra => return Err(CliError(format!("unexpected argument `{}`", extra))),
+        }
+    }
+    let selection = selection.ok_or_else(|| CliError("missing day, or `--all`".to_string()))?;
+    if selection == Selection::All && input.is_some() {
+        return Err(CliError(
+            "`--input` cannot be combined with `--all`".to_string(),
+        ));
+    }
+    if selection == Selection::All && !options.is_empty() {
+        return Err(CliError(
+            "`--set` cannot be combined with `--all`".to_string(),
+        ));
+    }
+    if record && input.is_some() {
+        return Err(CliError(
+            "`--record` cannot be combined with `--input`".to_string(),
+        ));
+    }
+    if record && !options.is_empty() {
+        return Err(CliError(
+            "`--record` cannot be combined with `--set`".to_string(),
+        ));
+    }
+    if jobs == 0 {
+        return Err(CliError("`--jobs` must be at least 1".to_string()));
+    }
+    let parts = match part {
+        Some(part) => vec![part],
+        None => vec![Part::One, Part::Two],
+    };
+    Ok(RunArgs {
+        selection,
+        parts,
+        input,
+        record,
+        jobs,
+        format,
+        options,
+    })
+}
+
+fn parse_bench_args(
+    mut args: impl Iterator<Item = String>,
+    days: &[usize],
+) -> Result<BenchArgs, CliError> {
+    let mut selection = None;
+    let mut iterations = 10;
+    let mut save = None;
+    let mut baseline = None;
+    let mut threshold: f64 = 10.0;
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--all" => selection = Some(Selection::All),
+            "--iterations" => iterations = parse_value(&arg, args.next())?,
+            "--save" => save = Some(expect_value(&arg, args.next())?),
+            "--baseline" => baseline = Some(expect_value(&arg, args.next())?),
+            "--threshold" => threshold = parse_value(&arg, args.next())?,
+            flag if flag.starts_with("--") => {
+                return Err(CliError(format!("unknown option `{}`", flag)));
+            }
+            day if selection.is_none() => selection = Some(Selection::Day(parse_day(day, days)?)),
+            extra => return Err(CliError(format!("unexpected argument `{}`", extra))),
+        }
+    }
+    let selection = selection.ok_or_else(|| CliError("missing day, or `--all`".to_string()))?;
+    if iterations == 0 {
+        return Err(CliError("`--iterations` must be at least 1".to_string()));
+    }
+    if !threshold.is_finite() || threshold < 0.0 {
+        return Err(CliError(
+            "`--threshold` must be a finite, non-negative percentage".to_string(),
+        ));
+    }
+    Ok(BenchArgs {
+        selection,
+        iterations,
+        save,
+        baseline,
+        threshold,
+    })
+}
+
+fn parse_fetch_args(
+    mut args: impl Iterator<Item = String>,
+    days: &[usize],
+) -> Result<FetchArgs, CliError> {
+    let mut selection = None;
+    let mut base_url = None;
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--all" => selection = Some(Selection::All),
+            "--base-url" => base_url = Some(expect_value(&arg, args.next())?),
+            flag if flag.starts_with("--") => {
+                return Err(CliError(format!("unknown option `{}`", flag)));
+            }
+            day if selection.is_none() => selection = Some(Selection::Day(parse_day(day, days)?)),
+            extra => return Err(CliError(format!("unexpected argument `{}`", extra))),
+        }
+    }
+    let selection = selection.ok_or_else(|| CliError("missing day, or `--all`".to_string()))?;
+    Ok(FetchArgs {
+        selection,
+        base_url,
+    })
+}
+
+fn parse_submit_args(
+    mut args: impl Iterator<Item = String>,
+    days: &[usize],
+) -> Result<SubmitArgs, CliError> {
+    let mut day = None;
+    let mut part = None;
+    let mut base_url = None;
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--base-url" => base_url = Some(expect_value(&arg, args.next())?),
+            flag if flag.starts_with("--") => {
+                return Err(CliError(format!("unknown option `{}`", flag)));
+            }
+            value if day.is_none() => day = Some(parse_day(value, days)?),
+            value if part.is_none() => part = Some(parse_part(value)?),
+            extra => return Err(CliError(format!("unexpected argument `{}`", extra))),
+        }
+    }
+    let day = day.ok_or_else(|| CliError("missing day".to_string()))?;
+    let part = part.ok_or_else(|| CliError("missing part".to_string()))?;
+    Ok(SubmitArgs {
+        day,
+        part,
+        base_url,
+    })
+}
+
+fn parse_new_args(mut args: impl Iterator<Item = String>) -> Result<NewArgs, CliError> {
+    let mut day = None;
+    let mut title = "TODO".to_string();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--title" => title = expect_value(&arg, args.next())?,
+            flag if flag.starts_with("--") => {
+                return Err(CliError(format!("unknown option `{}`", flag)));
+            }
+            value if day.is_none() => match value.parse::<usize>() {
+                Ok(number) if number > 0 => day = Some(number),
+                _ => {
+                    return Err(CliError(format!(
+                        "invalid day `{}`, expected a positive number",
+                        value
+                    )));
+                }
+            },
+            extra => return Err(CliError(format!("unexpected argument `{}`", extra))),
+        }
+    }
+    let day = day.ok_or_else(|| CliError("missing day".to_string()))?;
+    Ok(NewArgs { day, title })
+}
+
+fn parse_mst_args(mut args: impl Iterator<Item = String>) -> Result<MstArgs, CliError> {
+    let mut input = None;
+    let mut options = Options::default();
+    let mut format = TreeFormat::Csv;
+    let mut output = None;
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--input" => input = Some(expect_value(&arg, args.next())?),
+            "--set" => parse_setting(&mut options, args.next())?,
+            "--format" => format = parse_value(&arg, args.next())?,
+            "--output" => output = Some(expect_value(&arg, args.next())?),
+            flag if flag.starts_with("--") => {
+                return Err(CliError(format!("unknown option `{}`", flag)));
+            }
+            extra => return Err(CliError(format!("unexpected argument `{}`", extra))),
+        }
+    }
+    Ok(MstArgs {
+        input,
+        options,
+        format,
+        output,
+    })
+}
+
+fn parse_trace_args(mut args: impl Iterator<Item = String>) -> Result<TraceArgs, CliError> {
+    let mut input = None;
+    let mut options = Options::default();
+    let mut format = TraceFormat::Table;
+    let mut output = None;
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--input" => input = Some(expect_value(&arg, args.next())?),
+            "--set" => parse_setting(&mut options, args.next())?,
+            "--format" => format = parse_value(&arg, args.next())?,
+            "--output" => output = Some(expect_value(&arg, args.next())?),
+            flag if flag.starts_with("--") => {
+                return Err(CliError(format!("unknown option `{}`", flag)));
+            }
+            extra => return Err(CliError(format!("unexpected argument `{}`", extra))),
+        }
+    }
+    Ok(TraceArgs {
+        input,
+        options,
+        format,
+        output,
+    })
+}
+
+fn parse_scan_args(mut args: impl Iterator<Item = String>) -> Result<ScanArgs, CliError> {
+    let mut input = None;
+    let mut part = Part::One;
+    let mut options = Options::default();
+    let mut list = false;
+    let mut output = None;
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--input" => input = Some(expect_value(&arg, args.next())?),
+            "--part" => part = parse_part(&expect_value(&arg, args.next())?)?,
+            "--set" => parse_setting(&mut options, args.next())?,
+            "--list" => list = true,
+            "--output" => output = Some(expect_value(&arg, args.next())?),
+            flag if flag.starts_with("--") => {
+                return Err(CliError(format!("unknown option `{}`", flag)));
+            }
+            extra => return Err(CliError(format!("unexpected argument `{}`", extra))),
+        }
+    }
+    Ok(ScanArgs {
+        input,
+        part,
+        options,
+        list,
+        output,
+    })
+}
+
+pub fn parse_args(
+    args: impl IntoIterator<Item = String>,
+    days: &[usize],
+) -> Result<Command, CliError> {
+    let mut args = args.into_iter();
+    match args.next().as_deref() {
+        Some("run") => Ok(Command::Run(parse_run_args(args, days)?)),
+        Some("bench") => Ok(Command::Bench(parse_bench_args(args, days)?)),
+        Some("fetch") => Ok(Command::Fetch(parse_fetch_args(args, days)?)),
+        Some("submit") => Ok(Command::Submit(parse_submit_args(args, days)?)),
+        Some("new") => Ok(Command::New(parse_new_args(args)?)),
+        Some("mst") => Ok(Command::Mst(parse_mst_args(args)?)),
+        Some("trace") => Ok(Command::Trace(parse_trace_args(args)?)),
+        Some("scan") => Ok(Command::Scan(parse_scan_args(args)?)),
+        Some("help") | Some("--help") | Some("-h") => Ok(Command::Help),
+        Some(command) => Err(CliError(format!("unknown command `{}`", command))),
+        None => Err(CliError("missing command".to_string())),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Result<Command, CliError> {
+        let days = (1..=12).collect::<Vec<_>>();
+        parse_args(args.iter().map(|s| s.to_string()), &days)
+    }
+
+    #[test]
+    fn test_parse_args_run() {
+        assert_eq!(
+            parse(&["run", "3", "--part", "2", "--input", "example.txt"]),
+            Ok(Command::Run(RunArgs {
+                selection: Selection::Day(3),
+                parts: vec![Part::Two],
+                input: Some("example.txt".to_string()),
+                record: false,
+                jobs: 1,
+                format: Format::Text,
+                options: Options::default(),
+            })),
+        );
+        assert_eq!(
+            parse(&[
+                "run", "--all", "--record", "--jobs", "4", "--format", "json"
+            ]),
+            Ok(Command::Run(RunArgs {
+                selection: Selection::All,
+                parts: vec![Part::One, Part::Two],
+                input: None,
+                record: true,
+                jobs: 4,
+                format: Format::Json,
+                options: Options::default(),
+            })),
+        );
+        let mut options = Options::default();
+        options.set("metric", "manhattan");
+        options.set("connections", "10");
+        assert_eq!(
+            parse(&[
+                "run",
+                "8",
+                "--set",
+                "metric=chebyshev",
+                "--set",
+                "connections = 10",
+                "--set",
+                "metric=manhattan",
+            ]),
+            Ok(Command::Run(RunArgs {
+                selection: Selection::Day(8),
+                parts: vec![Part::One, Part::Two],
+                input: None,
+                record: false,
+                jobs: 1,
+                format: Format::Text,
+                options,
+            })),
+        );
+    }
+
+    #[test]
+    fn test_parse_args_bench() {
+        assert_eq!(
+            parse(&["bench", "8", "--iterations", "5", "--baseline", "base.txt"]),
+            Ok(Command::Bench(BenchArgs {
+                selection: Selection::Day(8),
+                iterations: 5,
+                save: None,
+                baseline: Some("base.txt".to_string()),
+                threshold: 10.0,
+            })),
+        );
+    }
+
+    #[test]
+    fn test_parse_args_bench_threshold() {
+        let threshold = |value: &str| match parse(&["bench", "8", "--threshold", value]) {
+            Ok(Command::Bench(args)) => Ok(args.threshold),
+            Ok(command) => panic!("unexpected command {:?}", command),
+            Err(CliError(message)) => Err(message),
+        };
+        assert_eq!(threshold("0"), Ok(0.0));
+        assert_eq!(threshold("2.5"), Ok(2.5));
+        for value in ["-5", "NaN", "inf", "-inf"] {
+            assert_eq!(
+                threshold(value),
+                Err("`--threshold` must be a finite, non-negative percentage".to_string()),
+            );
+        }
+    }
+
+    #[test]
+    fn test_parse_args_fetch() {
+        assert_eq!(
+            parse(&["fetch", "--all", "--base-url", "http://localhost:8080"]),
+            Ok(Command::Fetch(FetchArgs {
+                selection: Selection::All,
+                base_url: Some("http://localhost:8080".to_string()),
+            })),
+        );
+    }
+
+    #[test]
+    fn test_parse_args_submit() {
+        assert_eq!(
+            parse(&["submit", "4", "2"]),
+            Ok(Command::Submit(SubmitArgs {
+                day: 4,
+                part: Part::Two,
+                base_url: None,
+            })),
+        );
+    }
+
+    #[test]
+    fn test_parse_args_new() {
+        assert_eq!(
+            parse(&["new", "13", "--title", "Snow Globe"]),
+            Ok(Command::New(NewArgs {
+                day: 13,
+                title: "Snow Globe".to_string(),
+            })),
+        );
+    }
+
+    #[test]
+    fn test_parse_args_mst() {
+        let mut options = Options::default();
+        options.set("metric", "manhattan");
+        assert_eq!(
+            parse(&[
+                "mst",
+                "--format",
+                "dot",
+                "--set",
+                "metric=manhattan",
+                "--output",
+                "circuits.dot"
+            ]),
+            Ok(Command::Mst(MstArgs {
+                input: None,
+                options,
+                format: TreeFormat::Dot,
+                output: Some("circuits.dot".to_string()),
+            })),
+        );
+        assert!(parse(&["mst", "--format", "svg"]).is_err());
+        assert!(parse(&["mst", "--metric", "manhattan"]).is_err());
+    }
+
+    #[test]
+    fn test_parse_args_trace() {
+        let mut options = Options::default();
+        options.set("start", "0");
+        assert_eq!(
+            parse(&["trace", "--set", "start=0", "--format", "csv"]),
+            Ok(Command::Trace(TraceArgs {
+                input: None,
+                options,
+                format: TraceFormat::Csv,
+                output: None,
+            })),
+        );
+        assert!(parse(&["trace", "--format", "json"]).is_err());
+    }
+
+    #[test]
+    fn test_parse_args_scan() {
+        let mut options = Options::default();
+        options.set("repetitions", "2-3");
+        assert_eq!(
+            parse(&["scan", "--part", "2", "--list", "--set", "repetitions=2-3"]),
+            Ok(Command::Scan(ScanArgs {
+                input: None,
+                part: Part::Two,
+                options,
+                list: true,
+                output: None,
+            })),
+        );
+        assert!(parse(&["scan", "--part", "3"]).is_err());
+    }
+
+    #[test]
+    fn test_parse_args_rejects_invalid_input() {
+        assert!(parse(&[]).is_err());
+        assert!(parse(&["run"]).is_err());
+        assert!(parse(&["run", "13"]).is_err());
+        assert!(parse(&["run", "1", "--part", "3"]).is_err());
+        assert!(parse(&["run", "--all", "--input", "example.txt"]).is_err());
+        assert!(parse(&["run", "1", "2"]).is_err());
+        assert!(parse(&["run", "1", "--input", "example.txt", "--record"]).is_err());
+        assert!(parse(&["run", "--all", "--jobs", "0"]).is_err());
+        assert!(parse(&["run", "--all", "--format", "xml"]).is_err());
+        assert!(parse(&["run", "8", "--set", "metric"]).is_err());
+        assert!(parse(&["run", "--all", "--set", "metric=manhattan"]).is_err());
+        assert!(parse(&["bench", "1", "--iterations", "0"]).is_err());
+        assert!(parse(&["submit", "4"]).is_err());
+        assert!(parse(&["new", "0"]).is_err());
+    }
+}
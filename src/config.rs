@@ -0,0 +1,211 @@
+use std::time::Duration;
+
+pub const CONFIG_PATH: &str = "aoc.toml";
+
+const DEFAULT_BASE_URL: &str = "https://adventofcode.com";
+const DEFAULT_MIN_DELAY: Duration = Duration::from_secs(3);
+
+pub fn escape(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+#[derive(Debug, PartialEq)]
+pub enum Value {
+    Text(String),
+    List(Vec<String>),
+}
+
+impl Value {
+    pub fn into_text(self) -> Option<String> {
+        match self {
+            Value::Text(text) => Some(text),
+            Value::List(_) => None,
+        }
+    }
+
+    pub fn into_list(self) -> Option<Vec<String>> {
+        match self {
+            Value::Text(_) => None,
+            Value::List(items) => Some(items),
+        }
+    }
+}
+
+/// Parses the quoted string at the start of `value`, returning it and the rest of `value`.
+fn parse_quoted(value: &str) -> Option<(String, &str)> {
+    let quoted = value.strip_prefix('"')?;
+    let mut result = String::new();
+    let mut chars = quoted.char_indices();
+    while let Some((i, c)) = chars.next() {
+        match c {
+            '\\' => result.push(chars.next()?.1),
+            '"' => return Some((result, &quoted[i + 1..])),
+            _ => result.push(c),
+        }
+    }
+    None
+}
+
+fn parse_list(value: &str) -> Option<Vec<String>> {
+    let mut items = Vec::new();
+    let mut rest = value.strip_prefix('[')?.trim_start();
+    while !rest.starts_with(']') {
+        let (item, after) = parse_quoted(rest)?;
+        items.push(item);
+        rest = after.trim_start();
+        if let Some(after) = rest.strip_prefix(',') {
+            rest = after.trim_start();
+        } else if !rest.starts_with(']') {
+            return None;
+        }
+    }
+    rest[1..].trim().is_empty().then_some(items)
+}
+
+fn parse_value(value: &str) -> Option<Value> {
+    if value.starts_with('[') {
+        return parse_list(value).map(Value::List);
+    }
+    if value.starts_with('"') {
+        let (text, rest) = parse_quoted(value)?;
+        return rest.trim().is_empty().then_some(Value::Text(text));
+    }
+    Some(Value::Text(value.to_string()))
+}
+
+/// Parses `key = "value"` and `key = ["value", ...]` lines, skipping blank lines and `#` comments.
+///
+/// Returns the 0-based line index of each entry, or the index of the first malformed line.
+pub fn parse_entries(input: &str) -> Result<Vec<(usize, &str, Value)>, usize> {
+    let mut entries = Vec::new();
+    for (line_index, line) in input.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let (key, value) = line.split_once('=').ok_or(line_index)?;
+        let value = parse_value(value.trim()).ok_or(line_index)?;
+        entries.push((line_index, key.trim(), value));
+    }
+    Ok(entries)
+}
+
+/// Settings for talking to the puzzle server, read from `aoc.toml` and the environment.
+#[derive(Debug, PartialEq)]
+pub struct Config {
+    pub session: Option<String>,
+    pub base_url: String,
+    pub min_delay: Duration,
+    /// Contact details included in the User-Agent, as the puzzle server asks for.
+    pub contact: Option<String>,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            session: None,
+            base_url: DEFAULT_BASE_URL.to_string(),
+            min_delay: DEFAULT_MIN_DELAY,
+            contact: None,
+        }
+    }
+}
+
+impl Config {
+    /// Loads `path` if it exists, then applies the `AOC_SESSION` and `AOC_BASE_URL` environment
+    /// variables on top of it.
+    pub fn load(path: &str) -> Result<Self, String> {
+        let mut config = match std::fs::read_to_string(path) {
+            Ok(contents) => {
+                Self::parse(&contents).map_err(|error| format!("invalid `{}`: {}", path, error))?
+            }
+            Err(error) if error.kind() == std::io::ErrorKind::NotFound => Config::default(),
+            Err(error) => return Err(format!("failed to read `{}`: {}", path, error)),
+        };
+        if let Ok(session) = std::env::var("AOC_SESSION") {
+            config.session = Some(session);
+        }
+        if let Ok(base_url) = std::env::var("AOC_BASE_URL") {
+            config.base_url = base_url;
+        }
+        Ok(config)
+    }
+
+    fn parse(input: &str) -> Result<Self, String> {
+        let mut config = Config::default();
+        let entries = parse_entries(input)
+            .map_err(|line_index| format!("line {}: expected `key = \"value\"`", line_index + 1))?;
+        for (line_index, key, value) in entries {
+            let value = value
+                .into_text()
+                .ok_or_else(|| format!("line {}: expected a single value", line_index + 1))?;
+            match key {
+                "session" => config.session = Some(value),
+                "base_url" => config.base_url = value,
+                "contact" => config.contact = Some(value),
+                "min_delay_ms" => {
+                    let millis = value.parse::<u64>().map_err(|_| {
+                        format!("line {}: expected a number of milliseconds", line_index + 1)
+                    })?;
+                    config.min_delay = Duration::from_millis(millis);
+                }
+                _ => return Err(format!("line {}: unknown key `{}`", line_index + 1, key)),
+            }
+        }
+        Ok(config)
+    }
+
+    pub fn user_agent(&self) -> String {
+        let mut agent = format!(
+            "{}/{} (puzzle runner",
+            env!("CARGO_PKG_NAME"),
+            env!("CARGO_PKG_VERSION")
+        );
+        if let Some(contact) = &self.contact {
+            agent.push_str("; ");
+            agent.push_str(contact);
+        }
+        agent.push(')');
+        agent
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_value() {
+        assert_eq!(parse_value("12"), Some(Value::Text("12".to_string())));
+        assert_eq!(
+            parse_value("\"a \\\"b\\\"\""),
+            Some(Value::Text("a \"b\"".to_string()))
+        );
+        assert_eq!(
+            parse_value("[\"1\", \"2,3\" ]"),
+            Some(Value::List(vec!["1".to_string(), "2,3".to_string()]))
+        );
+        assert_eq!(parse_value("[]"), Some(Value::List(Vec::new())));
+        assert_eq!(parse_value("[\"1\" \"2\"]"), None);
+        assert_eq!(parse_value("\"1\" x"), None);
+    }
+
+    #[test]
+    fn test_config_parse() {
+        let config = Config::parse(
+            "# puzzle server\nsession = \"abc\"\nbase_url = \"http://localhost:8080\"\n\
+             min_delay_ms = 250\ncontact = \"me@example.com\"\n",
+        );
+        assert_eq!(
+            config,
+            Ok(Config {
+                session: Some("abc".to_string()),
+                base_url: "http://localhost:8080".to_string(),
+                min_delay: Duration::from_millis(250),
+                contact: Some("me@example.com".to_string()),
+            })
+        );
+        assert!(Config::parse("sesion = \"abc\"").is_err());
+        assert!(Config::parse("session = [\"abc\"]").is_err());
+    }
+}
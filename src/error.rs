@@ -0,0 +1,125 @@
+use std::fmt;
+use std::str::FromStr;
+
+/// An error in a puzzle input, pointing at the offending text.
+///
+/// Lines and columns are 1-based, columns count characters rather than bytes.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ParseError {
+    pub day: Option<usize>,
+    pub line: usize,
+    pub column: usize,
+    pub text: String,
+    pub message: String,
+}
+
+impl ParseError {
+    /// Creates an error for `token`, which must be a slice of `line`, the line at `line_index`.
+    pub fn at(line_index: usize, line: &str, token: &str, message: impl Into<String>) -> Self {
+        let offset = (token.as_ptr() as usize).saturating_sub(line.as_ptr() as usize);
+        let column = line
+            .get(..offset)
+            .map_or(0, |prefix| prefix.chars().count())
+            + 1;
+        Self {
+            day: None,
+            line: line_index + 1,
+            column,
+            text: token.to_string(),
+            message: message.into(),
+        }
+    }
+
+    /// Creates an error for something missing after the last line of the input.
+    pub fn at_end(line_count: usize, message: impl Into<String>) -> Self {
+        Self {
+            day: None,
+            line: line_count + 1,
+            column: 1,
+            text: String::new(),
+            message: message.into(),
+        }
+    }
+
+    pub fn with_day(self, day: usize) -> Self {
+        Self {
+            day: Some(day),
+            ..self
+        }
+    }
+
+    /// Renders the error together with the offending line of `input`, read from `path`.
+    pub fn render(&self, path: &str, input: &str) -> String {
+        let line_number = self.line.to_string();
+        let gutter = " ".repeat(line_number.len());
+        let source_line = input.lines().nth(self.line - 1).unwrap_or("");
+        let marker = format!(
+            "{}{}",
+            " ".repeat(self.column - 1),
+            "^".repeat(self.text.chars().count().max(1))
+        );
+        format!(
+            "{}\n{}--> {}:{}:{}\n{} |\n{} | {}\n{} | {}",
+            self,
+            gutter,
+            path,
+            self.line,
+            self.column,
+            gutter,
+            line_number,
+            source_line,
+            gutter,
+            marker
+        )
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(day) = self.day {
+            write!(f, "day {}, ", day)?;
+        }
+        write!(
+            f,
+            "line {}, column {}: {}",
+            self.line, self.column, self.message
+        )?;
+        if self.text.is_empty() {
+            f.write_str(", found nothing")
+        } else {
+            write!(f, ", found `{}`", self.text)
+        }
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// Parses `token`, a slice of `line`, ignoring surrounding whitespace.
+pub fn parse_number<T: FromStr>(
+    line_index: usize,
+    line: &str,
+    token: &str,
+) -> Result<T, ParseError> {
+    let token = token.trim();
+    token
+        .parse::<T>()
+        .map_err(|_| ParseError::at(line_index, line, token, "expected a number"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_error_points_at_token() {
+        let line = "4,x5,6";
+        let error = parse_number::<u64>(1, line, &line[2..4])
+            .unwrap_err()
+            .with_day(8);
+        assert_eq!((error.line, error.column), (2, 3));
+        assert_eq!(
+            error.to_string(),
+            "day 8, line 2, column 3: expected a number, found `x5`"
+        );
+    }
+}
@@ -0,0 +1,224 @@
+use crate::config::Config;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+pub const YEAR: u32 = 2025;
+
+/// Where the time of the last request is kept, so the delay also holds between runs.
+pub const LAST_REQUEST_PATH: &str = ".aoc-last-request";
+
+/// A client for the puzzle server that never sends requests closer together than `min_delay`.
+pub struct Client {
+    agent: ureq::Agent,
+    base_url: String,
+    session: Option<String>,
+    min_delay: Duration,
+    last_request: Option<Instant>,
+    /// A file holding the time of the last request of any run, in milliseconds since the epoch.
+    last_request_path: Option<PathBuf>,
+}
+
+fn describe_error(url: &str, error: ureq::Error) -> String {
+    match error {
+        ureq::Error::Status(status, response) => {
+            let body = response.into_string().unwrap_or_default();
+            format!("`{}` returned {}: {}", url, status, body.trim())
+        }
+        ureq::Error::Transport(transport) => format!("request to `{}` failed: {}", url, transport),
+    }
+}
+
+impl Client {
+    pub fn new(config: &Config) -> Self {
+        Self {
+            agent: ureq::AgentBuilder::new()
+                .user_agent(&config.user_agent())
+                .build(),
+            base_url: config.base_url.trim_end_matches('/').to_string(),
+            session: config.session.clone(),
+            min_delay: config.min_delay,
+            last_request: None,
+            last_request_path: None,
+        }
+    }
+
+    /// Also keeps the delay from requests of earlier runs, through the file at `path`.
+    pub fn with_last_request_path(mut self, path: impl Into<PathBuf>) -> Self {
+        self.last_request_path = Some(path.into());
+        self
+    }
+
+    fn url(&self, path: &str) -> String {
+        format!("{}/{}/{}", self.base_url, YEAR, path)
+    }
+
+    fn session_cookie(&self) -> Result<String, String> {
+        let session = self.session.as_deref().ok_or(
+            "no session token, set `session` in aoc.toml or the AOC_SESSION environment variable",
+        )?;
+        Ok(format!("session={}", session))
+    }
+
+    /// The time since the last request recorded in `last_request_path`, if there is one.
+    fn elapsed_since_recorded_request(&self) -> Option<Duration> {
+        let millis = std::fs::read_to_string(self.last_request_path.as_ref()?).ok()?;
+        let recorded = UNIX_EPOCH + Duration::from_millis(millis.trim().parse().ok()?);
+        // A time in the future, after the clock moved back, counts as just now.
+        Some(
+            SystemTime::now()
+                .duration_since(recorded)
+                .unwrap_or_default(),
+        )
+    }
+
+    fn wait_for_turn(&mut self) {
+        let elapsed = [
+            self.last_request.map(|last_request| last_request.elapsed()),
+            self.elapsed_since_recorded_request(),
+        ]
+        .into_iter()
+        .flatten()
+        .min();
+        if let Some(elapsed) = elapsed {
+            std::thread::sleep(self.min_delay.saturating_sub(elapsed));
+        }
+        self.last_request = Some(Instant::now());
+        if let Some(path) = &self.last_request_path {
+            let now = SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default();
+            // Failing to record the request only loses the delay for the next run.
+            let _ = std::fs::write(path, now.as_millis().to_string());
+        }
+    }
+
+    fn read_response(
+        url: &str,
+        response: Result<ureq::Response, ureq::Error>,
+    ) -> Result<String, String> {
+        response
+            .map_err(|error| describe_error(url, error))?
+            .into_string()
+            .map_err(|error| format!("failed to read the response of `{}`: {}", url, error))
+    }
+
+    pub fn get(&mut self, path: &str) -> Result<String, String> {
+        let cookie = self.session_cookie()?;
+        let url = self.url(path);
+        self.wait_for_turn();
+        let response = self.agent.get(&url).set("Cookie", &cookie).call();
+        Self::read_response(&url, response)
+    }
+
+    pub fn post_form(&mut self, path: &str, form: &[(&str, &str)]) -> Result<String, String> {
+        let cookie = self.session_cookie()?;
+        let url = self.url(path);
+        self.wait_for_turn();
+        let response = self.agent.post(&url).set("Cookie", &cookie).send_form(form);
+        Self::read_response(&url, response)
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum Fetched {
+    Cached,
+    Downloaded,
+}
+
+/// An input counts as cached once it has any content, so the empty files created by `new` are
+/// still downloaded.
+fn is_cached(path: &Path) -> bool {
+    std::fs::metadata(path).is_ok_and(|metadata| metadata.len() > 0)
+}
+
+/// Downloads the input of `day` to `path`, unless it is already there.
+pub fn fetch_input(client: &mut Client, day: usize, path: &Path) -> Result<Fetched, String> {
+    if is_cached(path) {
+        return Ok(Fetched::Cached);
+    }
+    let input = client.get(&format!("day/{}/input", day))?;
+    // Write to a temporary file first so an interrupted download never looks cached.
+    let partial_path = path.with_extension("part");
+    path.parent()
+        .map_or(Ok(()), std::fs::create_dir_all)
+        .and_then(|_| std::fs::write(&partial_path, input))
+        .and_then(|_| std::fs::rename(&partial_path, path))
+        .map_err(|error| format!("failed to write `{}`: {}", path.display(), error))?;
+    Ok(Fetched::Downloaded)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_server::TestServer;
+    use std::path::PathBuf;
+
+    fn config(server: &TestServer, min_delay: Duration) -> Config {
+        Config {
+            session: Some("abc".to_string()),
+            base_url: server.url.clone(),
+            min_delay,
+            contact: None,
+        }
+    }
+
+    fn temp_path(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("fetch-{}-{}/puzzle.txt", name, std::process::id()))
+    }
+
+    #[test]
+    fn test_fetch_input_downloads_once() {
+        let server = TestServer::start(|_| (200, "1\n2\n".to_string()));
+        let mut client = Client::new(&config(&server, Duration::ZERO));
+        let path = temp_path("once");
+        assert_eq!(fetch_input(&mut client, 3, &path), Ok(Fetched::Downloaded));
+        assert_eq!(fetch_input(&mut client, 3, &path), Ok(Fetched::Cached));
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "1\n2\n");
+        let requests = server.requests();
+        assert_eq!(requests.len(), 1);
+        assert_eq!(requests[0].method, "GET");
+        assert_eq!(requests[0].path, "/2025/day/3/input");
+        assert_eq!(requests[0].header("Cookie"), Some("session=abc"));
+        assert!(
+            requests[0]
+                .header("User-Agent")
+                .is_some_and(|agent| agent.starts_with("advent-of-code-2025/"))
+        );
+        std::fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
+
+    #[test]
+    fn test_fetch_input_reports_server_errors() {
+        let server = TestServer::start(|_| (404, "Not Found".to_string()));
+        let mut client = Client::new(&config(&server, Duration::ZERO));
+        let path = temp_path("error");
+        let error = fetch_input(&mut client, 13, &path).unwrap_err();
+        assert!(error.ends_with("/2025/day/13/input` returned 404: Not Found"));
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn test_client_waits_between_requests() {
+        let server = TestServer::start(|_| (200, String::new()));
+        let mut client = Client::new(&config(&server, Duration::from_millis(100)));
+        let start_time = Instant::now();
+        client.get("day/1/input").unwrap();
+        client.get("day/2/input").unwrap();
+        assert!(start_time.elapsed() >= Duration::from_millis(100));
+    }
+
+    #[test]
+    fn test_client_waits_for_requests_of_earlier_runs() {
+        let server = TestServer::start(|_| (200, String::new()));
+        let path = temp_path("last-request");
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        let start_time = Instant::now();
+        for day in 1..=2 {
+            let mut client = Client::new(&config(&server, Duration::from_millis(100)))
+                .with_last_request_path(&path);
+            client.get(&format!("day/{}/input", day)).unwrap();
+        }
+        assert!(start_time.elapsed() >= Duration::from_millis(100));
+        std::fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
+}
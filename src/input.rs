@@ -0,0 +1,71 @@
+use flate2::read::MultiGzDecoder;
+use std::io::Read;
+
+/// The path that stands for standard input.
+pub const STDIN_PATH: &str = "-";
+
+const GZIP_MAGIC: [u8; 2] = [0x1f, 0x8b];
+
+/// The name of `path` in messages.
+pub fn display_name(path: &str) -> &str {
+    if path == STDIN_PATH { "<stdin>" } else { path }
+}
+
+/// Decompresses `bytes` if they are gzip-compressed and checks that the result is UTF-8.
+fn decode(bytes: Vec<u8>) -> std::io::Result<String> {
+    let bytes = if bytes.starts_with(&GZIP_MAGIC) {
+        let mut decompressed = Vec::new();
+        MultiGzDecoder::new(bytes.as_slice()).read_to_end(&mut decompressed)?;
+        decompressed
+    } else {
+        bytes
+    };
+    String::from_utf8(bytes)
+        .map_err(|_| std::io::Error::new(std::io::ErrorKind::InvalidData, "not valid UTF-8"))
+}
+
+/// Reads a puzzle input from `path`, or from standard input when `path` is `-`. Gzip-compressed
+/// inputs are decompressed, whatever their name.
+pub fn read_puzzle_input(path: &str) -> Result<String, String> {
+    let bytes = if path == STDIN_PATH {
+        let mut bytes = Vec::new();
+        std::io::stdin().read_to_end(&mut bytes).map(|_| bytes)
+    } else {
+        std::fs::read(path)
+    };
+    bytes
+        .and_then(decode)
+        .map_err(|error| format!("failed to read `{}`: {}", display_name(path), error))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flate2::Compression;
+    use flate2::write::GzEncoder;
+    use std::io::Write;
+
+    #[test]
+    fn test_read_puzzle_input_decompresses_gzip() {
+        let path = std::env::temp_dir().join(format!("input-{}.txt.gz", std::process::id()));
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(b"L68\nR48\n").unwrap();
+        std::fs::write(&path, encoder.finish().unwrap()).unwrap();
+        let path = path.to_str().unwrap();
+        assert_eq!(read_puzzle_input(path), Ok("L68\nR48\n".to_string()));
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn test_read_puzzle_input_reports_errors() {
+        assert!(
+            read_puzzle_input("inputs/missing.txt")
+                .unwrap_err()
+                .starts_with("failed to read `inputs/missing.txt`")
+        );
+        assert_eq!(
+            decode(vec![0xff, 0xfe]).unwrap_err().to_string(),
+            "not valid UTF-8"
+        );
+    }
+}
@@ -1,50 +1,312 @@
-mod puzzle_1;
-mod puzzle_10;
-mod puzzle_11;
-mod puzzle_12;
-mod puzzle_2;
-mod puzzle_3;
-mod puzzle_4;
-mod puzzle_5;
-mod puzzle_6;
-mod puzzle_7;
-mod puzzle_8;
-mod puzzle_9;
+mod answers;
+mod bench;
+mod cli;
+mod config;
+mod error;
+mod fetch;
+mod input;
+mod output;
+mod registry;
+mod runner;
+mod scaffold;
+mod solution;
+mod submit;
+#[cfg(test)]
+mod test_server;
 mod utils;
 
-fn read_number() -> i32 {
-    let mut input = String::new();
-    std::io::stdin().read_line(&mut input).unwrap();
-    input.trim().parse::<i32>().unwrap()
-}
-
-fn read_puzzle_input(path: &str) -> String {
-    std::fs::read_to_string(path).unwrap()
-}
-
-fn main() {
-    let puzzles: Vec<(fn(&str), &str)> = vec![
-        (puzzle_1::solve_puzzle, "inputs/puzzle_1.txt"),
-        (puzzle_2::solve_puzzle, "inputs/puzzle_2.txt"),
-        (puzzle_3::solve_puzzle, "inputs/puzzle_3.txt"),
-        (puzzle_4::solve_puzzle, "inputs/puzzle_4.txt"),
-        (puzzle_5::solve_puzzle, "inputs/puzzle_5.txt"),
-        (puzzle_6::solve_puzzle, "inputs/puzzle_6.txt"),
-        (puzzle_7::solve_puzzle, "inputs/puzzle_7.txt"),
-        (puzzle_8::solve_puzzle, "inputs/puzzle_8.txt"),
-        (puzzle_9::solve_puzzle, "inputs/puzzle_9.txt"),
-        (puzzle_10::solve_puzzle, "inputs/puzzle_10.txt"),
-        (puzzle_11::solve_puzzle, "inputs/puzzle_11.txt"),
-        (puzzle_12::solve_puzzle, "inputs/puzzle_12.txt"),
-    ];
-    println!("Which puzzle would you like to run? [1-{}]", puzzles.len());
-    let puzzle_number = read_number();
-    if puzzle_number <= 0 || puzzle_number > puzzles.len() as i32 {
-        panic!("Invalid puzzle number");
-    }
-    let (solve_puzzle, path) = puzzles[(puzzle_number - 1) as usize];
-    let puzzle_input = read_puzzle_input(path);
-    let start_time = std::time::Instant::now();
-    solve_puzzle(&puzzle_input);
-    println!("Time elapsed: {:?}", start_time.elapsed());
+use answers::AnswerStore;
+use bench::Baseline;
+use cli::{
+    BenchArgs, Command, FetchArgs, MstArgs, NewArgs, RunArgs, ScanArgs, Selection, SubmitArgs,
+    TraceArgs,
+};
+use config::Config;
+use error::ParseError;
+use fetch::Fetched;
+use output::Format;
+use registry::{Registration, Registry};
+use runner::{PartStatus, Verification};
+use solution::{Answer, Options, Part, Solution};
+use std::path::Path;
+use std::process::ExitCode;
+use submit::{Outcome, Submission};
+
+registry::puzzles! {
+    puzzle_1,
+    puzzle_2,
+    puzzle_3,
+    puzzle_4,
+    puzzle_5,
+    puzzle_6,
+    puzzle_7,
+    puzzle_8,
+    puzzle_9,
+    puzzle_10,
+    puzzle_11,
+    puzzle_12,
+}
+
+/// Runs a single day and prints its answers, returning whether none of them failed verification.
+fn run_puzzle(
+    puzzle: &Registration,
+    path: &str,
+    parts: &[Part],
+    options: &Options,
+    verification: Option<&Verification>,
+) -> Result<bool, String> {
+    let puzzle_input = input::read_puzzle_input(path)?;
+    let run = runner::run_day(
+        puzzle.day,
+        puzzle.solve,
+        path,
+        &puzzle_input,
+        parts,
+        options,
+        verification,
+    )?;
+    println!("Day {}: {}", puzzle.day, puzzle.title);
+    println!("Parse: {:.2?}", run.report.parse_elapsed);
+    for (part, status) in run.report.parts.iter().zip(run.statuses.iter()) {
+        if *status == PartStatus::Unchecked {
+            println!("Part {}: {} [{:.2?}]", part.part, part.answer, part.elapsed);
+        } else {
+            println!(
+                "Part {}: {} ({}) [{:.2?}]",
+                part.part, part.answer, status, part.elapsed
+            );
+        }
+    }
+    println!("Time elapsed: {:.2?}", run.report.total_elapsed());
+    Ok(run.passed())
+}
+
+fn run(registry: &Registry, args: RunArgs) -> Result<bool, String> {
+    // Stored answers only apply to the checked-in inputs with the default settings.
+    let verification = (args.input.is_none() && args.options.is_empty()).then(|| Verification {
+        store: AnswerStore::new(answers::ANSWERS_DIR),
+        record: args.record,
+    });
+    let days = match args.selection {
+        Selection::Day(day) => {
+            let puzzle = registry
+                .get(day)
+                .expect("the day was checked when parsing arguments");
+            (puzzle.check_options)(&args.options)
+                .map_err(|error| format!("day {}: {}", day, error))?;
+            let path = args.input.as_deref().unwrap_or(puzzle.input_path);
+            if args.format == Format::Text {
+                return run_puzzle(
+                    puzzle,
+                    path,
+                    &args.parts,
+                    &args.options,
+                    verification.as_ref(),
+                );
+            }
+            vec![(day, puzzle.solve, path)]
+        }
+        Selection::All => registry
+            .iter()
+            .map(|puzzle| (puzzle.day, puzzle.solve, puzzle.input_path))
+            .collect(),
+    };
+    Ok(runner::run_all(
+        &days,
+        &args.parts,
+        &args.options,
+        verification.as_ref(),
+        args.jobs,
+        args.format,
+    ))
+}
+
+fn selected_puzzles(registry: &Registry, selection: &Selection) -> Vec<&'static Registration> {
+    match selection {
+        Selection::Day(day) => registry.get(*day).into_iter().collect(),
+        Selection::All => registry.iter().collect(),
+    }
+}
+
+/// Benchmarks the selected days, returning whether none of them regressed against the baseline.
+fn bench(registry: &Registry, args: BenchArgs) -> Result<bool, String> {
+    let baseline = args.baseline.as_deref().map(Baseline::load).transpose()?;
+    let mut results = Baseline::default();
+    let mut passed = true;
+    for puzzle in selected_puzzles(registry, &args.selection) {
+        let path = puzzle.input_path;
+        let puzzle_input = input::read_puzzle_input(path)?;
+        let stats = bench::bench_puzzle(puzzle.solve, &puzzle_input, args.iterations)
+            .map_err(|error| error.with_day(puzzle.day).render(path, &puzzle_input))?;
+        passed &= bench::print_stats(puzzle.day, &stats, baseline.as_ref(), args.threshold);
+        results.add(puzzle.day, &stats);
+    }
+    if let Some(path) = args.save {
+        results.save(&path)?;
+    }
+    Ok(passed)
+}
+
+fn client(base_url: Option<String>) -> Result<fetch::Client, String> {
+    let mut config = Config::load(config::CONFIG_PATH)?;
+    if let Some(base_url) = base_url {
+        config.base_url = base_url;
+    }
+    Ok(fetch::Client::new(&config).with_last_request_path(fetch::LAST_REQUEST_PATH))
+}
+
+/// Downloads the inputs of the selected days that are not in `inputs/` yet.
+fn fetch(registry: &Registry, args: FetchArgs) -> Result<bool, String> {
+    let mut client = client(args.base_url)?;
+    for puzzle in selected_puzzles(registry, &args.selection) {
+        let path = Path::new(puzzle.input_path);
+        match fetch::fetch_input(&mut client, puzzle.day, path)? {
+            Fetched::Cached => println!("Day {}: {} is already cached", puzzle.day, path.display()),
+            Fetched::Downloaded => println!("Day {}: downloaded {}", puzzle.day, path.display()),
+        }
+    }
+    Ok(true)
+}
+
+/// Solves a part and submits its answer, returning whether the answer is correct.
+fn submit(registry: &Registry, args: SubmitArgs) -> Result<bool, String> {
+    let puzzle = registry
+        .get(args.day)
+        .expect("the day was checked when parsing arguments");
+    let puzzle_input = input::read_puzzle_input(puzzle.input_path)?;
+    let report =
+        (puzzle.solve)(&puzzle_input, &[args.part], &Options::default()).map_err(|error| {
+            error
+                .with_day(args.day)
+                .render(puzzle.input_path, &puzzle_input)
+        })?;
+    let answer = &report.parts[0].answer;
+    if *answer == Answer::NotImplemented {
+        return Err(format!(
+            "part {} of day {} is not implemented",
+            args.part, args.day
+        ));
+    }
+    let store = AnswerStore::new(answers::ANSWERS_DIR);
+    let mut client = client(args.base_url)?;
+    let submission = submit::submit_answer(&mut client, &store, args.day, args.part, answer)?;
+    print!("Day {} part {}: {} ", args.day, args.part, answer);
+    match &submission {
+        Submission::AlreadyKnown => println!("is the recorded answer, not submitting it again"),
+        Submission::Differs { expected } => {
+            println!(
+                "differs from the recorded answer {}, not submitting it",
+                expected
+            )
+        }
+        Submission::KnownWrong { reason } => println!("is wrong, {}, not submitting it", reason),
+        Submission::Sent(outcome) => println!("is {}", outcome),
+    }
+    Ok(matches!(
+        submission,
+        Submission::AlreadyKnown | Submission::Sent(Outcome::Correct)
+    ))
+}
+
+fn new_day(args: NewArgs) -> Result<bool, String> {
+    for path in scaffold::new_day(Path::new("."), args.day, &args.title)? {
+        println!("Wrote {}", path.display());
+    }
+    Ok(true)
+}
+
+/// Checks the settings of day `S`, reads its input and writes what `render` makes of it to
+/// `output`, or to stdout.
+fn export<S: Solution>(
+    input_path: Option<&str>,
+    options: &Options,
+    output: Option<String>,
+    render: impl FnOnce(&str) -> Result<String, ParseError>,
+) -> Result<bool, String> {
+    S::check_options(options).map_err(|error| format!("day {}: {}", S::DAY, error))?;
+    let path = input_path.unwrap_or(S::INPUT_PATH);
+    let puzzle_input = input::read_puzzle_input(path)?;
+    let exported = render(&puzzle_input).map_err(|error| {
+        error
+            .with_day(S::DAY)
+            .render(input::display_name(path), &puzzle_input)
+    })?;
+    match output {
+        Some(output) => std::fs::write(&output, exported)
+            .map_err(|error| format!("failed to write `{}`: {}", output, error))?,
+        None => print!("{}", exported),
+    }
+    Ok(true)
+}
+
+/// Writes the minimum spanning tree of the day 8 junction boxes.
+fn mst(args: MstArgs) -> Result<bool, String> {
+    export::<puzzle_8::Puzzle>(
+        args.input.as_deref(),
+        &args.options,
+        args.output,
+        |puzzle_input| puzzle_8::export_spanning_tree(puzzle_input, &args.options, args.format),
+    )
+}
+
+/// Writes the position of the day 1 dial after every instruction line.
+fn trace(args: TraceArgs) -> Result<bool, String> {
+    export::<puzzle_1::Puzzle>(
+        args.input.as_deref(),
+        &args.options,
+        args.output,
+        |puzzle_input| puzzle_1::export_trace(puzzle_input, &args.options, args.format),
+    )
+}
+
+/// Writes the invalid IDs of every range of the day 2 input.
+fn scan(args: ScanArgs) -> Result<bool, String> {
+    export::<puzzle_2::Puzzle>(
+        args.input.as_deref(),
+        &args.options,
+        args.output,
+        |puzzle_input| puzzle_2::export_scan(puzzle_input, &args.options, args.part, args.list),
+    )
+}
+
+fn exit_code(result: Result<bool, String>) -> ExitCode {
+    match result {
+        Ok(true) => ExitCode::SUCCESS,
+        Ok(false) => ExitCode::FAILURE,
+        Err(error) => {
+            eprintln!("error: {}", error);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn main() -> ExitCode {
+    let registry = match Registry::new(PUZZLES) {
+        Ok(registry) => registry,
+        Err(error) => {
+            eprintln!("error: {}", error);
+            return ExitCode::FAILURE;
+        }
+    };
+    let command = match cli::parse_args(std::env::args().skip(1), &registry.days()) {
+        Ok(command) => command,
+        Err(error) => {
+            eprintln!("error: {}\n\n{}", error, cli::USAGE);
+            return ExitCode::from(2);
+        }
+    };
+    match command {
+        Command::Help => {
+            println!("{}", cli::USAGE);
+            ExitCode::SUCCESS
+        }
+        Command::Run(args) => exit_code(run(&registry, args)),
+        Command::Bench(args) => exit_code(bench(&registry, args)),
+        Command::Fetch(args) => exit_code(fetch(&registry, args)),
+        Command::Submit(args) => exit_code(submit(&registry, args)),
+        Command::New(args) => exit_code(new_day(args)),
+        Command::Mst(args) => exit_code(mst(args)),
+        Command::Trace(args) => exit_code(trace(args)),
+        Command::Scan(args) => exit_code(scan(args)),
+    }
 }
@@ -0,0 +1,266 @@
+use crate::answers::Verdict;
+use crate::runner::{DayOutcome, PartStatus};
+use crate::solution::Answer;
+use std::str::FromStr;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum Format {
+    #[default]
+    Text,
+    Json,
+    Csv,
+}
+
+impl FromStr for Format {
+    type Err = ();
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "text" => Ok(Format::Text),
+            "json" => Ok(Format::Json),
+            "csv" => Ok(Format::Csv),
+            _ => Err(()),
+        }
+    }
+}
+
+/// A single result, one per day and part, or one per day when the day failed to run.
+struct Record<'a> {
+    day: usize,
+    part: Option<String>,
+    answer: Option<&'a Answer>,
+    status: &'static str,
+    expected: Option<&'a str>,
+    duration_ns: Option<u128>,
+    input_path: &'a str,
+    input_hash: Option<String>,
+    error: Option<&'a str>,
+}
+
+const FIELDS: [&str; 9] = [
+    "day",
+    "part",
+    "answer",
+    "status",
+    "expected",
+    "duration_ns",
+    "input_path",
+    "input_hash",
+    "error",
+];
+
+fn status_label(status: &PartStatus) -> &'static str {
+    match status {
+        PartStatus::Checked(Verdict::Pass) => "PASS",
+        PartStatus::Checked(Verdict::Fail { .. }) => "FAIL",
+        PartStatus::Checked(Verdict::Unknown) => "UNKNOWN",
+        PartStatus::Recorded => "RECORDED",
+        PartStatus::Unchecked => "UNCHECKED",
+    }
+}
+
+fn records(outcomes: &[DayOutcome]) -> Vec<Record<'_>> {
+    let mut records = Vec::new();
+    for outcome in outcomes {
+        let input_hash = outcome.input_hash.map(|hash| format!("{:016x}", hash));
+        let run = match &outcome.result {
+            Ok(run) => run,
+            Err(error) => {
+                records.push(Record {
+                    day: outcome.day,
+                    part: None,
+                    answer: None,
+                    status: "ERROR",
+                    expected: None,
+                    duration_ns: None,
+                    input_path: &outcome.path,
+                    input_hash,
+                    error: Some(error),
+                });
+                continue;
+            }
+        };
+        for (part, status) in run.report.parts.iter().zip(run.statuses.iter()) {
+            records.push(Record {
+                day: outcome.day,
+                part: Some(part.part.to_string()),
+                answer: (part.answer != Answer::NotImplemented).then_some(&part.answer),
+                status: status_label(status),
+                expected: match status {
+                    PartStatus::Checked(Verdict::Fail { expected }) => Some(expected),
+                    _ => None,
+                },
+                duration_ns: Some(part.elapsed.as_nanos()),
+                input_path: &outcome.path,
+                input_hash: input_hash.clone(),
+                error: None,
+            });
+        }
+    }
+    records
+}
+
+fn json_string(value: &str) -> String {
+    let mut result = String::from("\"");
+    for c in value.chars() {
+        match c {
+            '"' => result.push_str("\\\""),
+            '\\' => result.push_str("\\\\"),
+            '\n' => result.push_str("\\n"),
+            '\r' => result.push_str("\\r"),
+            '\t' => result.push_str("\\t"),
+            c if c.is_control() => result.push_str(&format!("\\u{:04x}", c as u32)),
+            c => result.push(c),
+        }
+    }
+    result.push('"');
+    result
+}
+
+fn json_or_null<T>(value: Option<T>, to_json: impl FnOnce(T) -> String) -> String {
+    value.map_or_else(|| "null".to_string(), to_json)
+}
+
+impl Record<'_> {
+    fn json_values(&self) -> [String; 9] {
+        [
+            self.day.to_string(),
+            json_or_null(self.part.as_deref(), |part| part.to_string()),
+            json_or_null(self.answer, |answer| match answer {
+                Answer::Integer(value) => value.to_string(),
+                answer => json_string(&answer.to_string()),
+            }),
+            json_string(self.status),
+            json_or_null(self.expected, json_string),
+            json_or_null(self.duration_ns, |duration| duration.to_string()),
+            json_string(self.input_path),
+            json_or_null(self.input_hash.as_deref(), json_string),
+            json_or_null(self.error, json_string),
+        ]
+    }
+
+    fn csv_values(&self) -> [String; 9] {
+        [
+            self.day.to_string(),
+            self.part.clone().unwrap_or_default(),
+            self.answer.map(Answer::to_string).unwrap_or_default(),
+            self.status.to_string(),
+            self.expected.unwrap_or_default().to_string(),
+            self.duration_ns
+                .map(|duration| duration.to_string())
+                .unwrap_or_default(),
+            self.input_path.to_string(),
+            self.input_hash.clone().unwrap_or_default(),
+            self.error.unwrap_or_default().to_string(),
+        ]
+    }
+}
+
+/// Formats the outcomes as a JSON array with one object per line.
+pub fn to_json(outcomes: &[DayOutcome]) -> String {
+    let objects = records(outcomes)
+        .iter()
+        .map(|record| {
+            let fields = FIELDS
+                .iter()
+                .zip(record.json_values())
+                .map(|(name, value)| format!("\"{}\": {}", name, value))
+                .collect::<Vec<_>>();
+            format!("  {{{}}}", fields.join(", "))
+        })
+        .collect::<Vec<_>>();
+    if objects.is_empty() {
+        return "[]\n".to_string();
+    }
+    format!("[\n{}\n]\n", objects.join(",\n"))
+}
+
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// Formats the outcomes as CSV with a header row.
+pub fn to_csv(outcomes: &[DayOutcome]) -> String {
+    let mut csv = FIELDS.join(",") + "\n";
+    for record in records(outcomes) {
+        let values = record.csv_values().map(|value| csv_field(&value));
+        csv.push_str(&values.join(","));
+        csv.push('\n');
+    }
+    csv
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::runner::DayRun;
+    use crate::solution::{Part, PartReport, Report};
+    use std::time::Duration;
+
+    fn outcomes() -> Vec<DayOutcome> {
+        vec![
+            DayOutcome {
+                day: 1,
+                path: "inputs/puzzle_1.txt".to_string(),
+                input_hash: Some(0xabc),
+                result: Ok(DayRun {
+                    report: Report {
+                        parse_elapsed: Duration::ZERO,
+                        parts: vec![
+                            PartReport {
+                                part: Part::One,
+                                answer: Answer::Integer(3),
+                                elapsed: Duration::from_nanos(1500),
+                            },
+                            PartReport {
+                                part: Part::Two,
+                                answer: Answer::Text("a,\"b\"".to_string()),
+                                elapsed: Duration::from_nanos(20),
+                            },
+                        ],
+                    },
+                    statuses: vec![
+                        PartStatus::Checked(Verdict::Pass),
+                        PartStatus::Checked(Verdict::Fail {
+                            expected: "c".to_string(),
+                        }),
+                    ],
+                }),
+            },
+            DayOutcome {
+                day: 2,
+                path: "inputs/puzzle_2.txt".to_string(),
+                input_hash: None,
+                result: Err("failed to read `inputs/puzzle_2.txt`".to_string()),
+            },
+        ]
+    }
+
+    #[test]
+    fn test_to_json() {
+        assert_eq!(
+            to_json(&outcomes()),
+            r#"[
+  {"day": 1, "part": 1, "answer": 3, "status": "PASS", "expected": null, "duration_ns": 1500, "input_path": "inputs/puzzle_1.txt", "input_hash": "0000000000000abc", "error": null},
+  {"day": 1, "part": 2, "answer": "a,\"b\"", "status": "FAIL", "expected": "c", "duration_ns": 20, "input_path": "inputs/puzzle_1.txt", "input_hash": "0000000000000abc", "error": null},
+  {"day": 2, "part": null, "answer": null, "status": "ERROR", "expected": null, "duration_ns": null, "input_path": "inputs/puzzle_2.txt", "input_hash": null, "error": "failed to read `inputs/puzzle_2.txt`"}
+]
+"#
+        );
+    }
+
+    #[test]
+    fn test_to_csv() {
+        assert_eq!(
+            to_csv(&outcomes()),
+            "day,part,answer,status,expected,duration_ns,input_path,input_hash,error\n\
+             1,1,3,PASS,,1500,inputs/puzzle_1.txt,0000000000000abc,\n\
+             1,2,\"a,\"\"b\"\"\",FAIL,c,20,inputs/puzzle_1.txt,0000000000000abc,\n\
+             2,,,ERROR,,,inputs/puzzle_2.txt,,failed to read `inputs/puzzle_2.txt`\n"
+        );
+    }
+}
@@ -1,58 +1,506 @@
-use crate::utils::extract_lines;
-
-fn parse_dial_movement(line: &str) -> i32 {
-    let direction = line.trim().chars().next().unwrap();
-    let distance = line.trim().chars().skip(1).collect::<String>().parse::<i32>().unwrap();
-    distance * match direction {
-        'R' => 1,
-        'L' => -1,
-        _ => panic!("Invalid direction"),
-    }
-}
-
-fn solve_part_1(lines: &[&str]) {
-    let mut position = 50;
-    let mut count = 0;
-    for line in lines {
-        position += parse_dial_movement(line);
-        position %= 100;
-        if position == 0 {
-            count += 1;
-        }
-    }
-    println!("Part 1: {}", count);
-}
-
-fn solve_part_2(lines: &[&str]) {
-    let mut position = 50;
-    let mut count = 0;
-    for line in lines {
-        let dial_movement = parse_dial_movement(line);
-        if dial_movement > 0 {
-            for _ in 0..dial_movement {
-                position += 1;
-                position %= 100;
-                if position == 0 {
-                    count += 1;
-                }
+use crate::error::ParseError;
+use crate::solution::{Answer, Options, Solution};
+use crate::utils::text::Text;
+use std::num::ParseIntError;
+use std::str::FromStr;
+
+const DEFAULT_SIZE: i64 = 100;
+const DEFAULT_TARGET: i64 = 0;
+
+fn parse_dial_movement(line_index: usize, line: &str) -> Result<i64, ParseError> {
+    let movement = line.trim();
+    let mut chars = movement.chars();
+    let direction = match chars.next() {
+        Some('R') => 1,
+        Some('L') => -1,
+        _ => {
+            return Err(ParseError::at(
+                line_index,
+                line,
+                movement,
+                "expected a movement starting with `L` or `R`",
+            ));
+        }
+    };
+    let distance = chars.as_str().trim();
+    if distance.is_empty() || !distance.bytes().all(|byte| byte.is_ascii_digit()) {
+        return Err(ParseError::at(
+            line_index,
+            line,
+            distance,
+            "expected a distance like `68`",
+        ));
+    }
+    // Bounding the distance keeps the dial's arithmetic, and the total hits, far from overflowing.
+    let distance = distance.parse::<u32>().map_err(|_| {
+        ParseError::at(
+            line_index,
+            line,
+            distance,
+            format!("expected a distance of at most {}", u32::MAX),
+        )
+    })?;
+    Ok(i64::from(distance) * direction)
+}
+
+/// How often the dial stopped at a target position, and how often any click reached it.
+#[derive(Clone, Debug, PartialEq)]
+struct TargetHits {
+    position: i64,
+    landed: usize,
+    passed: usize,
+}
+
+/// The dial of the safe, pointing at one of `size` positions and counting hits on its targets.
+#[derive(Clone, Debug)]
+struct Dial {
+    size: i64,
+    position: i64,
+    targets: Vec<TargetHits>,
+}
+
+impl Dial {
+    fn new(size: i64, start: i64, targets: &[i64]) -> Result<Self, String> {
+        if size < 1 {
+            return Err(format!(
+                "the dial needs at least one position, not {}",
+                size
+            ));
+        }
+        let check_position = |position: i64| {
+            if (0..size).contains(&position) {
+                Ok(position)
+            } else {
+                Err(format!(
+                    "position {} is not on a dial of size {}",
+                    position, size
+                ))
             }
+        };
+        if targets.is_empty() {
+            return Err("the dial needs at least one target".to_string());
+        }
+        let mut target_positions = targets
+            .iter()
+            .map(|&target| check_position(target))
+            .collect::<Result<Vec<_>, _>>()?;
+        target_positions.sort_unstable();
+        target_positions.dedup();
+        Ok(Self {
+            size,
+            position: check_position(start)?,
+            targets: target_positions
+                .into_iter()
+                .map(|position| TargetHits {
+                    position,
+                    landed: 0,
+                    passed: 0,
+                })
+                .collect(),
+        })
+    }
+
+    /// Counts the clicks of a `movement` from the current position that end on `target`.
+    fn passes(&self, target: i64, movement: i64) -> usize {
+        // The clicks already made towards the target, as if the dial had started at its previous
+        // visit in the direction of the movement: every `size` clicks from there reach it once.
+        let clicks_since_target = if movement >= 0 {
+            (self.position - target).rem_euclid(self.size)
         } else {
-            for _ in 0..dial_movement.abs() {
-                position -= 1;
-                position %= 100;
-                if position == 0 {
-                    count += 1;
-                }
+            (target - self.position).rem_euclid(self.size)
+        };
+        // Widened, as a dial may have almost `i64::MAX` positions.
+        let clicks = i128::from(clicks_since_target) + i128::from(movement.unsigned_abs());
+        (clicks / i128::from(self.size)) as usize
+    }
+
+    /// Turns the dial by `movement` clicks, right when positive, and returns how many of those
+    /// clicks ended on a target.
+    fn rotate(&mut self, movement: i64) -> usize {
+        let passes = self
+            .targets
+            .iter()
+            .map(|target| self.passes(target.position, movement))
+            .collect::<Vec<_>>();
+        self.position = (i128::from(self.position) + i128::from(movement))
+            .rem_euclid(i128::from(self.size)) as i64;
+        for (target, passes) in self.targets.iter_mut().zip(&passes) {
+            target.passed += passes;
+            if target.position == self.position {
+                target.landed += 1;
             }
         }
+        passes.iter().sum()
+    }
+}
+
+/// A comma-separated list of dial positions.
+struct Targets(Vec<i64>);
+
+impl FromStr for Targets {
+    type Err = ParseIntError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        value
+            .split(',')
+            .map(|target| target.trim().parse())
+            .collect::<Result<_, _>>()
+            .map(Targets)
+    }
+}
+
+fn read_options(options: &Options) -> Result<Dial, String> {
+    options.check_keys(&["size", "start", "targets"])?;
+    let targets = options
+        .get::<Targets>("targets")?
+        .map_or(vec![DEFAULT_TARGET], |targets| targets.0);
+    let size = options.get("size")?.unwrap_or(DEFAULT_SIZE);
+    // Halfway round, which is 50 on the puzzle's dial.
+    let start = options.get("start")?.unwrap_or(size / 2);
+    Dial::new(size, start, &targets)
+}
+
+pub struct Safe {
+    dial: Dial,
+    dial_movements: Vec<i64>,
+}
+
+fn rotate_all(safe: &Safe) -> Dial {
+    let mut dial = safe.dial.clone();
+    for &dial_movement in &safe.dial_movements {
+        dial.rotate(dial_movement);
     }
-    println!("Part 2: {}", count);
+    dial
 }
 
-pub fn solve_puzzle(input: &str) {
-    let lines = extract_lines(input);
-    solve_part_1(&lines);
-    solve_part_2(&lines);
+/// The total number of hits, listed per target when there are several.
+fn hits_answer(dial: &Dial, count: impl Fn(&TargetHits) -> usize) -> Answer {
+    match dial.targets.as_slice() {
+        [target] => count(target).into(),
+        targets => Answer::Text(
+            targets
+                .iter()
+                .map(|target| format!("{} at {}", count(target), target.position))
+                .collect::<Vec<_>>()
+                .join(", "),
+        ),
+    }
 }
 
+fn solve_part_1(safe: &Safe) -> Answer {
+    hits_answer(&rotate_all(safe), |target| target.landed)
+}
+
+fn solve_part_2(safe: &Safe) -> Answer {
+    hits_answer(&rotate_all(safe), |target| target.passed)
+}
+
+/// The dial before and after a single instruction line.
+struct TraceStep {
+    line_index: usize,
+    start: i64,
+    movement: i64,
+    end: i64,
+    hits: usize,
+}
+
+fn trace(input: &str, dial: &Dial) -> Result<Vec<TraceStep>, ParseError> {
+    let mut dial = dial.clone();
+    Text::new(input)
+        .lines()
+        .map(|(line_index, line)| {
+            let movement = parse_dial_movement(line_index, line)?;
+            let start = dial.position;
+            let hits = dial.rotate(movement);
+            Ok(TraceStep {
+                line_index,
+                start,
+                movement,
+                end: dial.position,
+                hits,
+            })
+        })
+        .collect()
+}
 
+fn format_movement(movement: i64) -> String {
+    if movement < 0 {
+        format!("L{}", -movement)
+    } else {
+        format!("R{}", movement)
+    }
+}
+
+fn trace_rows(steps: &[TraceStep]) -> Vec<[String; 5]> {
+    let header = ["line", "start", "movement", "end", "hits"].map(str::to_string);
+    let rows = steps.iter().map(|step| {
+        [
+            (step.line_index + 1).to_string(),
+            step.start.to_string(),
+            format_movement(step.movement),
+            step.end.to_string(),
+            step.hits.to_string(),
+        ]
+    });
+    std::iter::once(header).chain(rows).collect()
+}
+
+fn trace_to_csv(steps: &[TraceStep]) -> String {
+    trace_rows(steps)
+        .iter()
+        .map(|row| row.join(",") + "\n")
+        .collect()
+}
+
+fn trace_to_table(steps: &[TraceStep]) -> String {
+    let rows = trace_rows(steps);
+    let mut widths = [0; 5];
+    for row in &rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.len());
+        }
+    }
+    let mut table = String::new();
+    for (index, row) in rows.iter().enumerate() {
+        let cells = row
+            .iter()
+            .zip(widths)
+            .map(|(cell, width)| format!("{:>width$}", cell))
+            .collect::<Vec<_>>();
+        table.push_str(&cells.join("  "));
+        table.push('\n');
+        if index == 0 {
+            table.push_str(&"-".repeat(widths.iter().sum::<usize>() + 2 * 4));
+            table.push('\n');
+        }
+    }
+    table
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum TraceFormat {
+    Csv,
+    Table,
+}
+
+impl FromStr for TraceFormat {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "csv" => Ok(TraceFormat::Csv),
+            "table" => Ok(TraceFormat::Table),
+            _ => Err(format!("unknown trace format `{}`", value)),
+        }
+    }
+}
+
+/// Follows the dial through a puzzle input, set up by checked `options`, and renders every
+/// instruction line in `format`.
+pub fn export_trace(
+    input: &str,
+    options: &Options,
+    format: TraceFormat,
+) -> Result<String, ParseError> {
+    let dial = read_options(options).expect("options are checked before tracing");
+    let steps = trace(input, &dial)?;
+    Ok(match format {
+        TraceFormat::Csv => trace_to_csv(&steps),
+        TraceFormat::Table => trace_to_table(&steps),
+    })
+}
+
+pub struct Puzzle;
+
+impl Solution for Puzzle {
+    const DAY: usize = 1;
+    const TITLE: &'static str = "Secret Entrance";
+    const INPUT_PATH: &'static str = "inputs/puzzle_1.txt";
+
+    type Input = Safe;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        Self::parse_with_options(input, &Options::default())
+    }
+
+    fn check_options(options: &Options) -> Result<(), String> {
+        read_options(options).map(|_| ())
+    }
+
+    fn parse_with_options(input: &str, options: &Options) -> Result<Self::Input, ParseError> {
+        Ok(Safe {
+            dial: read_options(options).expect("options are checked before parsing"),
+            dial_movements: Text::new(input)
+                .lines()
+                .map(|(line_index, line)| parse_dial_movement(line_index, line))
+                .collect::<Result<_, _>>()?,
+        })
+    }
+
+    fn part_1(safe: &Self::Input) -> Answer {
+        solve_part_1(safe)
+    }
+
+    fn part_2(safe: &Self::Input) -> Answer {
+        solve_part_2(safe)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solution::{Part, solve_example};
+    use crate::utils::random::Random;
+    const EXAMPLE: &str = "\
+L68
+L30
+R48
+L5
+R60
+L55
+L1
+L99
+R14
+L82
+";
+
+    #[test]
+    fn test_part_1_example() {
+        assert_eq!(
+            solve_example::<Puzzle>(EXAMPLE, Part::One),
+            Answer::Integer(3)
+        );
+    }
+
+    #[test]
+    fn test_part_2_example() {
+        assert_eq!(
+            solve_example::<Puzzle>(EXAMPLE, Part::Two),
+            Answer::Integer(6)
+        );
+    }
+
+    fn default_dial() -> Dial {
+        Dial::new(DEFAULT_SIZE, DEFAULT_SIZE / 2, &[DEFAULT_TARGET]).unwrap()
+    }
+
+    /// Turns the dial one click at a time, counting the clicks that end on `target`.
+    fn simulate(size: i64, position: i64, target: i64, movement: i64) -> (i64, usize) {
+        let step = movement.signum();
+        let mut position = position;
+        let mut count = 0;
+        for _ in 0..movement.abs() {
+            position = (position + step).rem_euclid(size);
+            if position == target {
+                count += 1;
+            }
+        }
+        (position, count)
+    }
+
+    #[test]
+    fn test_rotate_matches_simulation() {
+        let mut random = Random::new(12345);
+        let mut next = |bound: u64| random.below(bound) as i64;
+        for _ in 0..200 {
+            let size = next(120) + 1;
+            let targets = [next(size as u64), next(size as u64)];
+            let mut dial = Dial::new(size, next(size as u64), &targets).unwrap();
+            for _ in 0..50 {
+                // Mostly short movements, with exact multiples of the size and long ones mixed in.
+                let distance = match next(4) {
+                    0 => size * next(5),
+                    1 => next(1000),
+                    _ => next(2 * size as u64),
+                };
+                let movement = if next(2) == 0 { distance } else { -distance };
+                let expected = dial
+                    .targets
+                    .iter()
+                    .map(|target| simulate(size, dial.position, target.position, movement))
+                    .collect::<Vec<_>>();
+                let passes = dial
+                    .targets
+                    .iter()
+                    .map(|target| dial.passes(target.position, movement))
+                    .collect::<Vec<_>>();
+                dial.rotate(movement);
+                for ((position, count), passes) in expected.into_iter().zip(passes) {
+                    assert_eq!((dial.position, passes), (position, count));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_rotate_long_movements() {
+        let mut dial = default_dial();
+        assert_eq!(dial.rotate(1_000_000_000), 10_000_000);
+        assert_eq!(dial.position, 50);
+        assert_eq!(dial.rotate(-50), 1);
+        assert_eq!(dial.rotate(-300), 3);
+        assert_eq!(dial.rotate(300), 3);
+        assert_eq!(dial.position, 0);
+        let mut dial = Dial::new(i64::MAX, i64::MAX - 1, &[0]).unwrap();
+        assert_eq!(dial.rotate(i64::from(u32::MAX)), 1);
+        assert_eq!(dial.position, i64::from(u32::MAX) - 1);
+    }
+
+    #[test]
+    fn test_parse_dial_movement() {
+        assert_eq!(parse_dial_movement(0, "R68"), Ok(68));
+        assert_eq!(parse_dial_movement(0, "L4294967295"), Ok(-4294967295));
+        for line in [
+            "R-5",
+            "L+5",
+            "R",
+            "L5x",
+            "R4294967296",
+            "L-9223372036854775808",
+        ] {
+            assert!(parse_dial_movement(0, line).is_err(), "{}", line);
+        }
+    }
+
+    #[test]
+    fn test_export_trace() {
+        let input = "L68\n\nR248\n";
+        assert_eq!(
+            export_trace(input, &Options::default(), TraceFormat::Csv),
+            Ok("line,start,movement,end,hits\n1,50,L68,82,1\n3,82,R248,30,3\n".to_string())
+        );
+        assert_eq!(
+            export_trace(input, &Options::default(), TraceFormat::Table),
+            Ok("\
+line  start  movement  end  hits
+--------------------------------
+   1     50       L68   82     1
+   3     82      R248   30     3
+"
+            .to_string())
+        );
+    }
+
+    #[test]
+    fn test_several_targets() {
+        let mut options = Options::default();
+        options.set("size", "10");
+        options.set("start", "5");
+        options.set("targets", "0, 3");
+        let safe = Puzzle::parse_with_options("R5\nL2\nR25\n", &options).unwrap();
+        assert_eq!(
+            Puzzle::part_1(&safe),
+            Answer::Text("1 at 0, 1 at 3".to_string())
+        );
+        assert_eq!(
+            Puzzle::part_2(&safe),
+            Answer::Text("4 at 0, 3 at 3".to_string())
+        );
+        options.set("targets", "0,10");
+        assert!(Puzzle::check_options(&options).is_err());
+        let mut options = Options::default();
+        options.set("size", "10");
+        let safe = Puzzle::parse_with_options("L5\n", &options).unwrap();
+        assert_eq!(Puzzle::part_1(&safe), Answer::Integer(1));
+    }
+}
@@ -1,61 +1,127 @@
-use good_lp::{constraint, default_solver, variable, Expression, Solution, SolverModel};
+use crate::error::{ParseError, parse_number};
+use crate::solution::{Answer, Solution};
+use crate::utils::text::Text;
+use good_lp::{Expression, Solution as _, SolverModel, constraint, default_solver, variable};
 use std::collections::VecDeque;
 
 #[derive(Debug)]
-struct Input {
+pub struct Input {
     desired_lights: Vec<bool>,
     buttons: Vec<Vec<usize>>,
     joltage: Vec<usize>,
 }
 
 impl Input {
-    fn parse(input: &str) -> Input {
-        let pairs = input.split(" ").collect::<Vec<&str>>();
-        Input {
-            desired_lights: Self::parse_desired_lights(pairs[0]),
-            buttons: Self::parse_buttons(&pairs[1..pairs.len() - 1]),
-            joltage: Self::parse_joltage(pairs[pairs.len() - 1]),
+    fn parse(line_index: usize, line: &str) -> Result<Input, ParseError> {
+        let pairs = line.split_whitespace().collect::<Vec<&str>>();
+        if pairs.len() < 3 {
+            return Err(ParseError::at(
+                line_index,
+                line,
+                line.trim(),
+                "expected lights, at least one button and joltage requirements",
+            ));
         }
+        let desired_lights = Self::parse_desired_lights(line_index, line, pairs[0])?;
+        let buttons = Self::parse_buttons(
+            line_index,
+            line,
+            &pairs[1..pairs.len() - 1],
+            desired_lights.len(),
+        )?;
+        let joltage = Self::parse_joltage(line_index, line, pairs[pairs.len() - 1])?;
+        Ok(Input {
+            desired_lights,
+            buttons,
+            joltage,
+        })
+    }
+
+    fn strip_delimiters<'t>(
+        line_index: usize,
+        line: &str,
+        token: &'t str,
+        open: char,
+        close: char,
+    ) -> Result<&'t str, ParseError> {
+        token
+            .strip_prefix(open)
+            .and_then(|s| s.strip_suffix(close))
+            .ok_or_else(|| {
+                ParseError::at(
+                    line_index,
+                    line,
+                    token,
+                    format!("expected a value wrapped in `{}` and `{}`", open, close),
+                )
+            })
     }
 
-    fn parse_buttons(buttons: &[&str]) -> Vec<Vec<usize>> {
+    fn parse_buttons(
+        line_index: usize,
+        line: &str,
+        buttons: &[&str],
+        light_count: usize,
+    ) -> Result<Vec<Vec<usize>>, ParseError> {
         buttons
             .iter()
             .map(|s| {
-                let s = s.trim();
-                s[1..s.len() - 1]
+                Self::strip_delimiters(line_index, line, s, '(', ')')?
                     .split(",")
-                    .map(|s| s.parse::<usize>().unwrap())
+                    .map(|s| {
+                        let light_index = parse_number::<usize>(line_index, line, s)?;
+                        if light_index >= light_count {
+                            return Err(ParseError::at(
+                                line_index,
+                                line,
+                                s.trim(),
+                                format!("expected a light index below {}", light_count),
+                            ));
+                        }
+                        Ok(light_index)
+                    })
                     .collect()
             })
             .collect()
     }
 
-    fn parse_desired_lights(input: &str) -> Vec<bool> {
-        input
-            .chars()
-            .filter(|c| c != &'[' && c != &']')
-            .map(|c| c == '#')
-            .collect::<Vec<bool>>()
+    fn parse_desired_lights(
+        line_index: usize,
+        line: &str,
+        input: &str,
+    ) -> Result<Vec<bool>, ParseError> {
+        let lights = Self::strip_delimiters(line_index, line, input, '[', ']')?;
+        lights
+            .char_indices()
+            .map(|(i, c)| match c {
+                '#' => Ok(true),
+                '.' => Ok(false),
+                _ => Err(ParseError::at(
+                    line_index,
+                    line,
+                    &lights[i..i + c.len_utf8()],
+                    "expected `#` or `.`",
+                )),
+            })
+            .collect()
     }
 
-    fn parse_joltage(input: &str) -> Vec<usize> {
-        let input = input.trim();
-        input[1..input.len() - 1]
+    fn parse_joltage(line_index: usize, line: &str, input: &str) -> Result<Vec<usize>, ParseError> {
+        Self::strip_delimiters(line_index, line, input, '{', '}')?
             .split(",")
-            .map(|s| s.parse::<usize>().unwrap())
+            .map(|s| parse_number(line_index, line, s))
             .collect()
     }
 }
 
 struct LightState<'t> {
-    desired_lights: &'t Vec<bool>,
+    desired_lights: &'t [bool],
     lights: Vec<bool>,
     press_count: usize,
 }
 
 impl<'t> LightState<'t> {
-    fn new(desired_lights: &'t Vec<bool>) -> Self {
+    fn new(desired_lights: &'t [bool]) -> Self {
         Self {
             desired_lights,
             lights: vec![false; desired_lights.len()],
@@ -67,7 +133,7 @@ impl<'t> LightState<'t> {
         self.lights == *self.desired_lights
     }
 
-    fn toggle_lights(&self, light_indices: &Vec<usize>) -> Vec<bool> {
+    fn toggle_lights(&self, light_indices: &[usize]) -> Vec<bool> {
         let mut lights = self.lights.clone();
         for i in light_indices {
             lights[*i] = !lights[*i];
@@ -75,7 +141,7 @@ impl<'t> LightState<'t> {
         lights
     }
 
-    fn press_button(&self, button: &Vec<usize>) -> Self {
+    fn press_button(&self, button: &[usize]) -> Self {
         LightState {
             desired_lights: self.desired_lights,
             lights: self.toggle_lights(button),
@@ -98,15 +164,15 @@ fn get_min_button_press_count_for_lights(input: &Input) -> usize {
     }
 }
 
-fn solve_part_1(inputs: &Vec<Input>) {
+fn solve_part_1(inputs: &[Input]) -> usize {
     let mut result = 0;
     for input in inputs {
-        result += get_min_button_press_count_for_lights(&input);
+        result += get_min_button_press_count_for_lights(input);
     }
-    println!("Part 1: {}", result);
+    result
 }
 
-fn get_min_button_press_count_for_joltage(input: &Input) -> usize {
+fn get_min_button_press_count_for_joltage(input: &Input) -> Option<usize> {
     let mut problem_vars = vec![];
     let mut problem = good_lp::ProblemVariables::new();
     for _ in 0..input.buttons.len() {
@@ -123,27 +189,95 @@ fn get_min_button_press_count_for_joltage(input: &Input) -> usize {
         }
         model.add_constraint(constraint!(expr == (target_val as i32)));
     }
-    match model.solve() {
-        Ok(solution) => solution.eval(&objective) as usize,
-        Err(_) => {
-            panic!("Failed to solve model");
-        }
-    }
+    let solution = model.solve().ok()?;
+    Some(solution.eval(&objective) as usize)
 }
 
-fn solve_part_2(inputs: &Vec<Input>) {
+fn solve_part_2(inputs: &[Input]) -> Result<usize, String> {
     let mut result = 0;
-    for input in inputs {
-        result += get_min_button_press_count_for_joltage(&input);
+    for (index, input) in inputs.iter().enumerate() {
+        result += get_min_button_press_count_for_joltage(input).ok_or_else(|| {
+            format!(
+                "no button presses meet the joltage requirements of machine {}",
+                index + 1
+            )
+        })?;
+    }
+    Ok(result)
+}
+
+pub struct Puzzle;
+
+impl Solution for Puzzle {
+    const DAY: usize = 10;
+    const TITLE: &'static str = "Factory";
+    const INPUT_PATH: &'static str = "inputs/puzzle_10.txt";
+
+    type Input = Vec<Input>;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        Text::new(input)
+            .lines()
+            .map(|(line_index, line)| Input::parse(line_index, line))
+            .collect()
+    }
+
+    fn part_1(inputs: &Self::Input) -> Answer {
+        solve_part_1(inputs).into()
+    }
+
+    fn part_2(inputs: &Self::Input) -> Answer {
+        match solve_part_2(inputs) {
+            Ok(result) => result.into(),
+            Err(message) => Answer::Text(message),
+        }
     }
-    println!("Part 2: {}", result);
 }
 
-pub fn solve_puzzle(input: &str) {
-    let inputs = input
-        .lines()
-        .map(|l| Input::parse(l.trim()))
-        .collect::<Vec<_>>();
-    solve_part_1(&inputs);
-    solve_part_2(&inputs);
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solution::{Part, solve_example};
+    const EXAMPLE: &str = "\
+[.##.] (3) (1,3) (2) (2,3) (0,2) (0,1) {3,5,4,7}
+[...#.] (0,2,3,4) (2,3) (0,4) (0,1,2) (1,2,3,4) {7,5,12,7,2}
+[.###.#] (0,1,2,3,4) (0,3,4) (0,1,2,4,5) (1,2) {10,11,11,5,10,5}
+";
+
+    #[test]
+    fn test_part_1_example() {
+        assert_eq!(
+            solve_example::<Puzzle>(EXAMPLE, Part::One),
+            Answer::Integer(7)
+        );
+    }
+
+    #[test]
+    fn test_part_2_example() {
+        assert_eq!(
+            solve_example::<Puzzle>(EXAMPLE, Part::Two),
+            Answer::Integer(33)
+        );
+    }
+
+    #[test]
+    fn test_parse_rejects_machine_without_buttons() {
+        let error = Puzzle::parse("[.#] (0) {1,0}\n[.#] {1}\n").unwrap_err();
+        assert_eq!((error.line, error.column), (2, 1));
+        assert_eq!(
+            error.message,
+            "expected lights, at least one button and joltage requirements"
+        );
+    }
+
+    #[test]
+    fn test_part_2_with_unmet_joltage_requirements() {
+        let inputs = Puzzle::parse("[.#] (0) {0,1}\n").unwrap();
+        assert_eq!(
+            Puzzle::part_2(&inputs),
+            Answer::Text(
+                "no button presses meet the joltage requirements of machine 1".to_string()
+            )
+        );
+    }
 }
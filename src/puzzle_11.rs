@@ -1,3 +1,6 @@
+use crate::error::ParseError;
+use crate::solution::{Answer, Solution};
+use crate::utils::text::Text;
 use std::cell::RefCell;
 use std::collections::HashMap;
 
@@ -21,15 +24,33 @@ impl<'t> Path<'t> {
     }
 }
 
-fn parse_connections(input: &'_ str) -> HashMap<&'_ str, Vec<Path<'_>>> {
+pub type Connections = HashMap<String, Vec<String>>;
+
+fn parse_connections(input: &str) -> Result<Connections, ParseError> {
     let mut connections = HashMap::new();
-    for line in input.lines() {
-        let parts: Vec<&str> = line.split(": ").collect();
-        let key = parts[0];
-        let paths = parts[1].split(" ").map(|label| Path::new(label)).collect();
-        connections.insert(key, paths);
+    for (line_index, line) in Text::new(input).lines() {
+        let (key, labels) = line.trim().split_once(": ").ok_or_else(|| {
+            ParseError::at(
+                line_index,
+                line,
+                line.trim(),
+                "expected a connection like `aaa: bbb ccc`",
+            )
+        })?;
+        let labels = labels.split_whitespace().map(String::from).collect();
+        connections.insert(key.to_string(), labels);
     }
+    Ok(connections)
+}
+
+fn build_paths(connections: &Connections) -> HashMap<&str, Vec<Path<'_>>> {
     connections
+        .iter()
+        .map(|(key, labels)| {
+            let paths = labels.iter().map(|label| Path::new(label)).collect();
+            (key.as_str(), paths)
+        })
+        .collect()
 }
 
 fn count_connections_until_label(
@@ -62,12 +83,9 @@ fn count_connections_until_label_going_through_dac_and_fft(
     let next_labels = connections.get(from_label).unwrap();
     let mut result = 0;
     for path in next_labels {
-        match *path.count.get(&(found_dac, found_fft)).unwrap().borrow() {
-            Some(count) => {
-                result += count;
-                continue;
-            }
-            _ => (),
+        if let Some(count) = *path.count.get(&(found_dac, found_fft)).unwrap().borrow() {
+            result += count;
+            continue;
         }
         let path_result = if path.label == to_label {
             if found_dac && found_fft { 1 } else { 0 }
@@ -90,16 +108,81 @@ fn count_connections_until_label_going_through_dac_and_fft(
     result
 }
 
-pub fn solve_puzzle(input: &str) {
-    let connections = parse_connections(input);
-    let result = count_connections_until_label(&connections.clone(), "you", "out");
-    println!("Part 1: {}", result);
-    let result = count_connections_until_label_going_through_dac_and_fft(
-        &connections,
-        "svr",
-        "out",
-        false,
-        false,
-    );
-    println!("Part 2: {}", result);
+pub struct Puzzle;
+
+impl Solution for Puzzle {
+    const DAY: usize = 11;
+    const TITLE: &'static str = "Reactor";
+    const INPUT_PATH: &'static str = "inputs/puzzle_11.txt";
+
+    type Input = Connections;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        parse_connections(input)
+    }
+
+    fn part_1(connections: &Self::Input) -> Answer {
+        count_connections_until_label(&build_paths(connections), "you", "out").into()
+    }
+
+    fn part_2(connections: &Self::Input) -> Answer {
+        count_connections_until_label_going_through_dac_and_fft(
+            &build_paths(connections),
+            "svr",
+            "out",
+            false,
+            false,
+        )
+        .into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solution::{Part, solve_example};
+    const PART_1_EXAMPLE: &str = "\
+aaa: you hhh
+you: bbb ccc
+bbb: ddd eee
+ccc: ddd eee fff
+ddd: ggg
+eee: out
+fff: out
+ggg: out
+hhh: ccc fff iii
+iii: out
+";
+
+    const PART_2_EXAMPLE: &str = "\
+svr: aaa bbb
+aaa: fft
+fft: ccc
+bbb: tty
+tty: ccc
+ccc: ddd eee
+ddd: hub
+hub: fff
+eee: dac
+dac: fff
+fff: ggg hhh
+ggg: out
+hhh: out
+";
+
+    #[test]
+    fn test_part_1_example() {
+        assert_eq!(
+            solve_example::<Puzzle>(PART_1_EXAMPLE, Part::One),
+            Answer::Integer(5)
+        );
+    }
+
+    #[test]
+    fn test_part_2_example() {
+        assert_eq!(
+            solve_example::<Puzzle>(PART_2_EXAMPLE, Part::Two),
+            Answer::Integer(2)
+        );
+    }
 }
@@ -1,3 +1,7 @@
+use crate::error::{ParseError, parse_number};
+use crate::solution::{Answer, Solution};
+use crate::utils::grid::{Grid, Position};
+use crate::utils::text::{NumberedLine, Text};
 use std::fmt::Debug;
 
 const PRESENT_SIZE: usize = 3;
@@ -29,43 +33,63 @@ impl PresentShape {
         rotated_data
     }
 
-    fn parse(input: &[&str]) -> PresentShape {
-        let mut last_variant = Self::parse_base_variant(input);
+    fn parse(input: &[NumberedLine]) -> Result<PresentShape, ParseError> {
+        let (header_index, header) = input[0];
+        if !header.trim().ends_with(':') {
+            return Err(ParseError::at(
+                header_index,
+                header,
+                header.trim(),
+                "expected a shape index like `0:`",
+            ));
+        }
+        let mut last_variant = Self::parse_base_variant(header_index, &input[1..])?;
         let mut required_spaces = 0;
         for row in last_variant.iter() {
             required_spaces += row.iter().filter(|&&b| b).count();
         }
-        let mut variants = vec![last_variant.clone()];
+        let mut variants = Vec::new();
         for _ in 0..=3 {
-            last_variant = Self::rotate_left(&last_variant);
-            if variants.iter().any(|v| *v == last_variant) {
-                continue;
-            }
-            variants.push(last_variant.clone());
-            let flipped_variant = Self::flip_horizontally(&last_variant);
-            if variants.iter().any(|v| *v == flipped_variant) {
-                continue;
+            for variant in [last_variant, Self::flip_horizontally(&last_variant)] {
+                if !variants.contains(&variant) {
+                    variants.push(variant);
+                }
             }
-            variants.push(flipped_variant);
+            last_variant = Self::rotate_left(&last_variant);
         }
-        PresentShape {
+        Ok(PresentShape {
             required_spaces,
             variants,
-        }
+        })
     }
 
-    fn parse_base_variant(input: &[&str]) -> PresentData {
+    fn parse_base_variant(
+        header_index: usize,
+        input: &[NumberedLine],
+    ) -> Result<PresentData, ParseError> {
+        if input.len() != PRESENT_SIZE {
+            return Err(ParseError::at_end(
+                header_index + input.len(),
+                format!("expected a shape of {} rows", PRESENT_SIZE),
+            ));
+        }
+        let grid = Grid::parse(input, "`#` or `.`", |c| match c {
+            '#' => Some(true),
+            '.' => Some(false),
+            _ => None,
+        })?;
         let mut base_variant: PresentData = [[false; PRESENT_SIZE]; PRESENT_SIZE];
-        let data = input
-            .iter()
-            .map(|line| line.chars().map(|c| c == '#').collect::<Vec<_>>())
-            .collect::<Vec<_>>();
-        for y in 0..PRESENT_SIZE {
-            for x in 0..PRESENT_SIZE {
-                base_variant[y][x] = data[y][x];
-            }
+        for (y, &(line_index, line)) in input.iter().enumerate() {
+            base_variant[y] = grid.row(y).try_into().map_err(|_| {
+                ParseError::at(
+                    line_index,
+                    line,
+                    line,
+                    format!("expected a row of {} cells", PRESENT_SIZE),
+                )
+            })?;
         }
-        base_variant
+        Ok(base_variant)
     }
 }
 
@@ -77,158 +101,304 @@ struct TreeArea {
 }
 
 impl TreeArea {
-    fn parse(input: &&str) -> Self {
-        let parts = input.split(": ").collect::<Vec<&str>>();
-        let dimensions = parts[0]
-            .split("x")
-            .map(|s| s.parse::<usize>().unwrap())
-            .collect::<Vec<usize>>();
-        let presents = parts[1]
-            .split(" ")
-            .map(|s| s.parse::<usize>().unwrap())
-            .collect::<Vec<usize>>();
-        Self {
-            width: dimensions[0],
-            height: dimensions[1],
-            presents,
+    fn parse(line_index: usize, line: &str, shape_count: usize) -> Result<Self, ParseError> {
+        let (dimensions, presents) = line.trim().split_once(": ").ok_or_else(|| {
+            ParseError::at(
+                line_index,
+                line,
+                line.trim(),
+                "expected a tree area like `4x4: 0 2`",
+            )
+        })?;
+        let (width, height) = dimensions.split_once("x").ok_or_else(|| {
+            ParseError::at(
+                line_index,
+                line,
+                dimensions,
+                "expected dimensions like `4x4`",
+            )
+        })?;
+        let width = parse_number::<usize>(line_index, line, width)?;
+        let height = parse_number::<usize>(line_index, line, height)?;
+        if width < PRESENT_SIZE || height < PRESENT_SIZE {
+            return Err(ParseError::at(
+                line_index,
+                line,
+                dimensions,
+                format!("expected an area of at least {0}x{0}", PRESENT_SIZE),
+            ));
+        }
+        let presents = presents
+            .split_whitespace()
+            .map(|s| parse_number::<usize>(line_index, line, s))
+            .collect::<Result<Vec<usize>, _>>()?;
+        if presents.len() > shape_count {
+            return Err(ParseError::at(
+                line_index,
+                line,
+                line.trim(),
+                format!("expected at most {} present counts", shape_count),
+            ));
         }
+        Ok(Self {
+            width,
+            height,
+            presents,
+        })
     }
 }
 
-fn parse_present_shapes(lines: &[&str]) -> Vec<PresentShape> {
+fn parse_tree_areas(
+    lines: &[NumberedLine],
+    shape_count: usize,
+) -> Result<Vec<TreeArea>, ParseError> {
     lines
-        .split(|line| line.trim() == "")
-        .map(|lines| PresentShape::parse(&lines[1..]))
+        .iter()
+        .map(|&(line_index, line)| TreeArea::parse(line_index, line, shape_count))
         .collect()
 }
 
-fn parse_tree_areas(lines: &[&str]) -> Vec<TreeArea> {
-    lines.iter().map(TreeArea::parse).collect()
+pub struct Farm {
+    present_shapes: Vec<PresentShape>,
+    tree_areas: Vec<TreeArea>,
 }
 
-fn parse_input(input: &str) -> (Vec<PresentShape>, Vec<TreeArea>) {
-    let lines = input.lines().collect::<Vec<&str>>();
-    let last_empty_line_index = lines.iter().rposition(|l| l.trim() == "").unwrap();
-    let present_shapes = parse_present_shapes(&lines[..last_empty_line_index]);
-    let tree_areas = parse_tree_areas(&lines[last_empty_line_index + 1..]);
-    (present_shapes, tree_areas)
+fn parse_input(input: &str) -> Result<Farm, ParseError> {
+    let text = Text::new(input);
+    let blocks = text.blocks();
+    let Some((tree_areas, present_shapes)) = blocks
+        .split_last()
+        .filter(|(_, present_shapes)| !present_shapes.is_empty())
+    else {
+        return Err(ParseError::at_end(
+            text.line_count(),
+            "expected a blank line between the present shapes and the tree areas",
+        ));
+    };
+    let present_shapes = present_shapes
+        .iter()
+        .copied()
+        .map(PresentShape::parse)
+        .collect::<Result<Vec<_>, _>>()?;
+    let tree_areas = parse_tree_areas(tree_areas, present_shapes.len())?;
+    Ok(Farm {
+        present_shapes,
+        tree_areas,
+    })
 }
 
-fn place_remaining_presents(
-    area: &Vec<Vec<bool>>,
-    area_width: usize,
-    area_height: usize,
-    present_shapes: &[PresentShape],
-    remaining_presents: &[usize],
-) -> bool {
-    if remaining_presents.iter().all(|&p| p == 0) {
-        return true;
+/// The filled cells of a variant as offsets, in reading order.
+fn filled_cells(present_data: &PresentData) -> Vec<Position> {
+    (0..PRESENT_SIZE)
+        .flat_map(|y| (0..PRESENT_SIZE).map(move |x| (x, y)))
+        .filter(|&(x, y)| present_data[y][x])
+        .collect()
+}
+
+/// A search that fills the area cell by cell in reading order: the first empty cell is either
+/// covered by a present whose first filled cell lands on it, or left empty while the slack lasts.
+struct Packing<'s> {
+    area: Grid<bool>,
+    /// The filled cells of every variant of every shape.
+    shape_cells: &'s [Vec<Vec<Position>>],
+    remaining_presents: Vec<usize>,
+}
+
+impl Packing<'_> {
+    /// The top-left corner that puts the first filled cell of a variant on `(x, y)`.
+    fn corner(cells: &[Position], (x, y): Position) -> Option<Position> {
+        Some((x.checked_sub(cells[0].0)?, y.checked_sub(cells[0].1)?))
     }
-    for (present_index, &remaining_present_count) in remaining_presents.iter().enumerate() {
-        if remaining_present_count == 0 {
-            continue;
-        }
-        let updated_remaining_presents = remaining_presents
-            .iter()
-            .enumerate()
-            .map(|(i, &count)| if i == present_index { count - 1 } else { count })
-            .collect::<Vec<_>>();
-        if try_placing_present(
-            area,
-            area_width,
-            area_height,
-            present_shapes,
-            present_shapes.get(present_index).unwrap(),
-            &updated_remaining_presents,
-        ) {
-            return true;
+
+    fn fits(&self, cells: &[Position], (x, y): Position) -> bool {
+        cells.iter().all(|&(x_offset, y_offset)| {
+            let position = (x + x_offset, y + y_offset);
+            position.0 < self.area.width()
+                && position.1 < self.area.height()
+                && !self.area[position]
+        })
+    }
+
+    fn place(&mut self, cells: &[Position], (x, y): Position, filled: bool) {
+        for &(x_offset, y_offset) in cells {
+            self.area[(x + x_offset, y + y_offset)] = filled;
         }
     }
-    false
-}
 
-fn does_present_fit(area: &Vec<Vec<bool>>, present_data: &PresentData, x: usize, y: usize) -> bool {
-    for y_offset in 0..PRESENT_SIZE {
-        for x_offset in 0..PRESENT_SIZE {
-            if present_data[y_offset][x_offset] == false {
+    /// Fills the area from the cell at `start` on, leaving at most `slack` more cells empty. The
+    /// first empty cell is covered by a present, or left empty.
+    fn fill(&mut self, start: usize, slack: usize) -> bool {
+        if self.remaining_presents.iter().all(|&count| count == 0) {
+            return true;
+        }
+        let width = self.area.width();
+        let Some(index) = (start..width * self.area.height())
+            .find(|index| !self.area[(index % width, index / width)])
+        else {
+            return false;
+        };
+        let cell = (index % width, index / width);
+        let shape_cells = self.shape_cells;
+        for (shape_index, variants) in shape_cells.iter().enumerate() {
+            if self.remaining_presents[shape_index] == 0 {
                 continue;
             }
-            if area[y + y_offset][x + x_offset] {
-                return false;
+            for cells in variants {
+                let Some(corner) =
+                    Self::corner(cells, cell).filter(|&corner| self.fits(cells, corner))
+                else {
+                    continue;
+                };
+                self.place(cells, corner, true);
+                self.remaining_presents[shape_index] -= 1;
+                let found = self.fill(index + 1, slack);
+                self.remaining_presents[shape_index] += 1;
+                self.place(cells, corner, false);
+                if found {
+                    return true;
+                }
             }
         }
+        if slack == 0 {
+            return false;
+        }
+        self.area[cell] = true;
+        let found = self.fill(index + 1, slack - 1);
+        self.area[cell] = false;
+        found
     }
-    true
 }
 
-fn place_variant(
-    area: &Vec<Vec<bool>>,
-    present_data: &PresentData,
-    x: usize,
-    y: usize,
-) -> Vec<Vec<bool>> {
-    let mut area = area.clone();
-    for y_offset in 0..PRESENT_SIZE {
-        for x_offset in 0..PRESENT_SIZE {
-            area[y + y_offset][x + x_offset] |= present_data[y_offset][x_offset];
-        }
+fn presents_fit(tree_area: &TreeArea, present_shapes: &[PresentShape]) -> bool {
+    let required_spaces = tree_area
+        .presents
+        .iter()
+        .zip(present_shapes)
+        .map(|(&count, shape)| count * shape.required_spaces)
+        .sum::<usize>();
+    let Some(slack) = (tree_area.width * tree_area.height).checked_sub(required_spaces) else {
+        return false;
+    };
+    // Every present fits when each can have a square of its own.
+    let present_count = tree_area.presents.iter().sum::<usize>();
+    if (tree_area.width / PRESENT_SIZE) * (tree_area.height / PRESENT_SIZE) >= present_count {
+        return true;
+    }
+    let shape_cells = present_shapes
+        .iter()
+        .map(|shape| shape.variants.iter().map(filled_cells).collect())
+        .collect::<Vec<_>>();
+    // Rows along the shorter side keep the frontier of part-filled cells narrow. The variants
+    // include every rotation and flip, so the transposed area packs the same way.
+    let width = tree_area.width.min(tree_area.height);
+    let height = tree_area.width.max(tree_area.height);
+    Packing {
+        area: Grid::new(width, height, false),
+        shape_cells: &shape_cells,
+        remaining_presents: tree_area.presents.clone(),
     }
-    area
+    .fill(0, slack)
 }
 
-fn try_placing_present(
-    area: &Vec<Vec<bool>>,
-    area_width: usize,
-    area_height: usize,
-    present_shapes: &[PresentShape],
-    present_shape: &PresentShape,
-    remaining_presents: &[usize],
-) -> bool {
-    for variant in &present_shape.variants {
-        for y in 0..=area_height - PRESENT_SIZE {
-            for x in 0..=area_width - PRESENT_SIZE {
-                if !does_present_fit(area, variant, x, y) {
-                    continue;
-                }
-                let updated_area = place_variant(area, variant, x, y);
-                if place_remaining_presents(
-                    &updated_area,
-                    area_width,
-                    area_height,
-                    present_shapes,
-                    remaining_presents,
-                ) {
-                    return true;
-                }
-            }
-        }
+fn count_fitting_tree_areas(farm: &Farm) -> usize {
+    farm.tree_areas
+        .iter()
+        .filter(|tree_area| presents_fit(tree_area, &farm.present_shapes))
+        .count()
+}
+
+pub struct Puzzle;
+
+impl Solution for Puzzle {
+    const DAY: usize = 12;
+    const TITLE: &'static str = "Christmas Tree Farm";
+    const INPUT_PATH: &'static str = "inputs/puzzle_12.txt";
+
+    type Input = Farm;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        parse_input(input)
+    }
+
+    fn part_1(farm: &Self::Input) -> Answer {
+        count_fitting_tree_areas(farm).into()
     }
-    false
 }
 
-pub fn solve_puzzle(input: &str) {
-    let (present_shapes, tree_areas) = parse_input(input);
-    let mut count = 0;
-    for (tree_index, tree_area) in tree_areas.iter().enumerate() {
-        let required_spaces = tree_area
-            .presents
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solution::{Part, solve_example};
+    const EXAMPLE: &str = "\
+0:
+###
+##.
+##.
+
+1:
+###
+##.
+.##
+
+2:
+.##
+###
+##.
+
+3:
+##.
+###
+##.
+
+4:
+###
+#..
+###
+
+5:
+###
+.#.
+###
+
+4x4: 0 0 0 0 2 0
+12x5: 1 0 1 0 2 2
+12x5: 1 0 1 0 3 2
+";
+
+    #[test]
+    fn test_part_1_example() {
+        assert_eq!(
+            solve_example::<Puzzle>(EXAMPLE, Part::One),
+            Answer::Integer(2)
+        );
+    }
+
+    #[test]
+    fn test_part_1_example_fitting_regions() {
+        let example = EXAMPLE.replace("12x5: 1 0 1 0 3 2\n", "");
+        assert_eq!(
+            solve_example::<Puzzle>(&example, Part::One),
+            Answer::Integer(2)
+        );
+    }
+
+    #[test]
+    fn test_part_1_example_with_trailing_blank_lines() {
+        let example = EXAMPLE.replace("12x5: 1 0 1 0 3 2\n", "\n\n");
+        assert_eq!(
+            solve_example::<Puzzle>(&example, Part::One),
+            Answer::Integer(2)
+        );
+    }
+
+    #[test]
+    fn test_present_shape_variants() {
+        let farm = parse_input("0:\n###\n#..\n...\n\n1:\n###\n.#.\n###\n\n3x3: 1 0\n").unwrap();
+        let variant_counts = farm
+            .present_shapes
             .iter()
-            .enumerate()
-            .map(|(i, &count)| count * present_shapes[i].required_spaces)
-            .sum::<usize>();
-        if required_spaces > tree_area.width * tree_area.height {
-            continue;
-        }
-        if place_remaining_presents(
-            &vec![vec![false; tree_area.width]; tree_area.height],
-            tree_area.width,
-            tree_area.height,
-            &present_shapes,
-            &tree_area.presents,
-        ) {
-            count += 1;
-        }
-        println!("Checked tree area {}/{}", tree_index + 1, tree_areas.len())
+            .map(|shape| shape.variants.len())
+            .collect::<Vec<_>>();
+        assert_eq!(variant_counts, [8, 2]);
     }
-    println!("Part 1: {}", count);
 }
@@ -1,53 +1,510 @@
-use onig::Regex;
-
-fn get_number_pairs(input: &str) -> Vec<(i64, i64)> {
-    input
-        .trim()
-        .split(",")
-        .map(|id_pair| {
-            let id_pair = id_pair.trim().split_once("-").unwrap();
-            let (id1, id2) = id_pair;
-            (id1.parse::<i64>().unwrap(), id2.parse::<i64>().unwrap())
-        })
-        .collect()
+use crate::error::{ParseError, parse_number};
+use crate::solution::{Answer, Options, Part, Solution};
+use crate::utils::text::Text;
+use std::iter::Sum;
+use std::ops::{Add, RangeInclusive, Sub};
+use std::str::FromStr;
+
+fn get_number_pairs(input: &str) -> Result<Vec<(i64, i64)>, ParseError> {
+    let mut number_pairs = vec![];
+    for (line_index, line) in Text::new(input).lines() {
+        for id_pair in line
+            .split(",")
+            .map(str::trim)
+            .filter(|id_pair| !id_pair.is_empty())
+        {
+            let (id1, id2) = id_pair.split_once("-").ok_or_else(|| {
+                ParseError::at(
+                    line_index,
+                    line,
+                    id_pair,
+                    "expected an ID range like `11-22`",
+                )
+            })?;
+            number_pairs.push((
+                parse_number(line_index, line, id1)?,
+                parse_number(line_index, line, id2)?,
+            ));
+        }
+    }
+    Ok(number_pairs)
 }
 
-fn is_invalid_id_part_1(id: &str) -> bool {
-    let regex = Regex::new(r"^(\d+)\1$").unwrap();
-    regex.is_match(id)
+/// The numbers of times a block may be repeated to make an invalid ID.
+#[derive(Clone, Debug, PartialEq)]
+enum Repetitions {
+    Exactly(u32),
+    Between { min: u32, max: Option<u32> },
+    AnyOf(Vec<u32>),
 }
 
-fn is_invalid_id_part_2(id: &str) -> bool {
-    let regex = Regex::new(r"^(\d+)\1+$").unwrap();
-    regex.is_match(id)
+impl Repetitions {
+    fn accepts(&self, count: u32) -> bool {
+        match self {
+            Repetitions::Exactly(exact) => count == *exact,
+            Repetitions::Between { min, max } => {
+                count >= *min && max.is_none_or(|max| count <= max)
+            }
+            Repetitions::AnyOf(counts) => counts.contains(&count),
+        }
+    }
 }
 
-fn solve_part_1(number_pairs: &[(i64, i64)]) {
-    let mut result = 0;
-    for (from, to) in number_pairs {
-        for id in *from..=*to {
-            if is_invalid_id_part_1(&id.to_string()) {
-                result += id;
+/// Parses `3` (exactly), `2+` (at least), `2-4` (between, inclusive) or `2,3,5` (any of).
+impl FromStr for Repetitions {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let parse_count = |count: &str| match count.trim().parse::<u32>() {
+            Ok(count) if count >= 2 => Ok(count),
+            _ => Err(format!(
+                "expected repetition counts of at least 2, found `{}`",
+                count.trim()
+            )),
+        };
+        let value = value.trim();
+        if let Some(min) = value.strip_suffix('+') {
+            Ok(Repetitions::Between {
+                min: parse_count(min)?,
+                max: None,
+            })
+        } else if let Some((min, max)) = value.split_once('-') {
+            let (min, max) = (parse_count(min)?, parse_count(max)?);
+            if min > max {
+                return Err(format!(
+                    "the range starts at {}, after its end at {}",
+                    min, max
+                ));
             }
+            Ok(Repetitions::Between {
+                min,
+                max: Some(max),
+            })
+        } else if value.contains(',') {
+            value
+                .split(',')
+                .map(parse_count)
+                .collect::<Result<_, _>>()
+                .map(Repetitions::AnyOf)
+        } else {
+            parse_count(value).map(Repetitions::Exactly)
+        }
+    }
+}
+
+/// Which IDs are invalid: those made of a block of at least `min_block_length` digits, repeated
+/// a number of times accepted by `repetitions`.
+#[derive(Clone, Debug, PartialEq)]
+struct Rule {
+    repetitions: Repetitions,
+    min_block_length: u32,
+}
+
+impl Rule {
+    fn accepts(&self, digits: u32, block_length: u32) -> bool {
+        block_length >= self.min_block_length && self.repetitions.accepts(digits / block_length)
+    }
+}
+
+/// The number and sum of the invalid IDs in a range.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+struct Tally {
+    count: i64,
+    sum: i128,
+}
+
+impl Add for Tally {
+    type Output = Tally;
+
+    fn add(self, other: Tally) -> Tally {
+        Tally {
+            count: self.count + other.count,
+            sum: self.sum + other.sum,
+        }
+    }
+}
+
+impl Sub for Tally {
+    type Output = Tally;
+
+    fn sub(self, other: Tally) -> Tally {
+        Tally {
+            count: self.count - other.count,
+            sum: self.sum - other.sum,
         }
     }
-    println!("Part 1: {}", result);
 }
 
-fn solve_part_2(number_pairs: &[(i64, i64)]) {
-    let mut result = 0;
-    for (from, to) in number_pairs {
-        for id in *from..=*to {
-            if is_invalid_id_part_2(&id.to_string()) {
-                result += id;
+impl Sum for Tally {
+    fn sum<I: Iterator<Item = Tally>>(tallies: I) -> Tally {
+        tallies.fold(Tally::default(), Add::add)
+    }
+}
+
+/// The number of decimal digits of a positive `value`.
+fn digit_count(value: i64) -> u32 {
+    value.ilog10() + 1
+}
+
+/// The multiplier that repeats a block of `block_length` digits to make `digits` digits:
+/// 1 + 10^block_length + 10^(2 * block_length) + ...
+fn repeat_multiplier(digits: u32, block_length: u32) -> i64 {
+    (0..digits / block_length)
+        .map(|index| 10_i64.pow(index * block_length))
+        .sum()
+}
+
+/// The blocks of `block_length` digits whose repetition with `multiplier` falls in `from..=to`.
+fn repeated_blocks(from: i64, to: i64, block_length: u32, multiplier: i64) -> RangeInclusive<i64> {
+    let first_block =
+        (from / multiplier + i64::from(from % multiplier > 0)).max(10_i64.pow(block_length - 1));
+    let last_block = (to / multiplier).min(10_i64.pow(block_length) - 1);
+    first_block..=last_block
+}
+
+/// Tallies the IDs in `from..=to` that are a block of `block_length` digits repeated to make
+/// `digits` digits.
+fn tally_repeated_blocks(from: i64, to: i64, digits: u32, block_length: u32) -> Tally {
+    let multiplier = repeat_multiplier(digits, block_length);
+    let blocks = repeated_blocks(from, to, block_length, multiplier);
+    if blocks.is_empty() {
+        return Tally::default();
+    }
+    let (first_block, last_block) = blocks.into_inner();
+    let count = last_block - first_block + 1;
+    Tally {
+        count,
+        sum: i128::from(multiplier)
+            * (i128::from(first_block) + i128::from(last_block))
+            * i128::from(count)
+            / 2,
+    }
+}
+
+/// The digit counts of the positive numbers in `from..=to`, or of a single digit when there are
+/// none, which repeat no block.
+fn digit_counts(from: i64, to: i64) -> RangeInclusive<u32> {
+    digit_count(from.max(1))..=digit_count(to.max(1))
+}
+
+/// Tallies the IDs in `from..=to` that `rule` makes invalid.
+fn tally_invalid_ids(from: i64, to: i64, rule: &Rule) -> Tally {
+    digit_counts(from, to)
+        .map(|digits| {
+            let block_lengths = (1..digits)
+                .filter(|block_length| digits % block_length == 0)
+                .collect::<Vec<_>>();
+            // The tallies of the IDs whose shortest repeated block has each of those lengths, so
+            // an ID like 222222 that repeats blocks of several lengths is only counted once.
+            let mut shortest_block_tallies = Vec::with_capacity(block_lengths.len());
+            for &block_length in &block_lengths {
+                let shorter_tally = block_lengths
+                    .iter()
+                    .zip(&shortest_block_tallies)
+                    .filter(|&(shorter, _)| block_length % shorter == 0)
+                    .map(|(_, &tally)| tally)
+                    .sum::<Tally>();
+                shortest_block_tallies
+                    .push(tally_repeated_blocks(from, to, digits, block_length) - shorter_tally);
             }
+            let accepted = block_lengths
+                .iter()
+                .filter(|&&block_length| rule.accepts(digits, block_length))
+                .collect::<Vec<_>>();
+            block_lengths
+                .iter()
+                .zip(&shortest_block_tallies)
+                .filter(|&(shortest, _)| {
+                    accepted
+                        .iter()
+                        .any(|&&block_length| block_length % shortest == 0)
+                })
+                .map(|(_, &tally)| tally)
+                .sum::<Tally>()
+        })
+        .sum()
+}
+
+/// Lists the IDs in `from..=to` that `rule` makes invalid, in increasing order.
+fn list_invalid_ids(from: i64, to: i64, rule: &Rule) -> Vec<i64> {
+    let mut ids = vec![];
+    for digits in digit_counts(from, to) {
+        for block_length in (1..digits).filter(|&block_length| {
+            digits % block_length == 0 && rule.accepts(digits, block_length)
+        }) {
+            let multiplier = repeat_multiplier(digits, block_length);
+            ids.extend(
+                repeated_blocks(from, to, block_length, multiplier).map(|block| block * multiplier),
+            );
+        }
+    }
+    ids.sort_unstable();
+    ids.dedup();
+    ids
+}
+
+/// The invalid IDs of a single input range.
+struct RangeScan {
+    from: i64,
+    to: i64,
+    tally: Tally,
+    /// The invalid IDs themselves, when they were asked for.
+    ids: Option<Vec<i64>>,
+}
+
+fn scan_ranges(number_pairs: &[(i64, i64)], rule: &Rule, list_ids: bool) -> Vec<RangeScan> {
+    number_pairs
+        .iter()
+        .map(|&(from, to)| RangeScan {
+            from,
+            to,
+            tally: tally_invalid_ids(from, to, rule),
+            ids: list_ids.then(|| list_invalid_ids(from, to, rule)),
+        })
+        .collect()
+}
+
+/// Sums the invalid IDs of every range, as text when the sum is too large for an integer answer.
+fn solve(number_pairs: &[(i64, i64)], rule: &Rule) -> Answer {
+    let sum = scan_ranges(number_pairs, rule, false)
+        .iter()
+        .map(|scan| scan.tally.sum)
+        .sum::<i128>();
+    i64::try_from(sum).map_or_else(|_| Answer::Text(sum.to_string()), Answer::Integer)
+}
+
+pub struct GiftShop {
+    number_pairs: Vec<(i64, i64)>,
+    /// Replaces the repetitions of both parts when set.
+    repetitions: Option<Repetitions>,
+    min_block_length: u32,
+}
+
+impl GiftShop {
+    /// The rule of `part`: blocks repeated exactly twice in part 1, at least twice in part 2.
+    fn rule(&self, part: Part) -> Rule {
+        let repetitions = self.repetitions.clone().unwrap_or(match part {
+            Part::One => Repetitions::Exactly(2),
+            Part::Two => Repetitions::Between { min: 2, max: None },
+        });
+        Rule {
+            repetitions,
+            min_block_length: self.min_block_length,
+        }
+    }
+}
+
+fn read_options(options: &Options) -> Result<(Option<Repetitions>, u32), String> {
+    options.check_keys(&["repetitions", "min_block"])?;
+    let min_block_length = options.get("min_block")?.unwrap_or(1);
+    if min_block_length == 0 {
+        return Err(
+            "invalid value `0` for setting `min_block`: a block has at least one digit".to_string(),
+        );
+    }
+    Ok((options.get("repetitions")?, min_block_length))
+}
+
+fn parse_gift_shop(input: &str, options: &Options) -> Result<GiftShop, ParseError> {
+    let (repetitions, min_block_length) =
+        read_options(options).expect("options are checked before parsing");
+    Ok(GiftShop {
+        number_pairs: get_number_pairs(input)?,
+        repetitions,
+        min_block_length,
+    })
+}
+
+/// Scans every range of a puzzle input with the rule of `part`, adjusted by checked `options`,
+/// and writes its count and sum of invalid IDs as CSV, followed by the IDs when `list_ids` is set.
+pub fn export_scan(
+    input: &str,
+    options: &Options,
+    part: Part,
+    list_ids: bool,
+) -> Result<String, ParseError> {
+    let gift_shop = parse_gift_shop(input, options)?;
+    let scans = scan_ranges(&gift_shop.number_pairs, &gift_shop.rule(part), list_ids);
+    let mut csv = if list_ids {
+        "from,to,count,sum,ids\n"
+    } else {
+        "from,to,count,sum\n"
+    }
+    .to_string();
+    for scan in scans {
+        csv.push_str(&format!(
+            "{},{},{},{}",
+            scan.from, scan.to, scan.tally.count, scan.tally.sum
+        ));
+        if let Some(ids) = scan.ids {
+            let ids = ids.iter().map(i64::to_string).collect::<Vec<_>>();
+            csv.push_str(&format!(",{}", ids.join(" ")));
         }
+        csv.push('\n');
     }
-    println!("Part 2: {}", result);
+    Ok(csv)
 }
 
-pub fn solve_puzzle(input: &str) {
-    let number_pairs = get_number_pairs(input);
-    solve_part_1(&number_pairs);
-    solve_part_2(&number_pairs)
+pub struct Puzzle;
+
+impl Solution for Puzzle {
+    const DAY: usize = 2;
+    const TITLE: &'static str = "Gift Shop";
+    const INPUT_PATH: &'static str = "inputs/puzzle_2.txt";
+
+    type Input = GiftShop;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        Self::parse_with_options(input, &Options::default())
+    }
+
+    fn check_options(options: &Options) -> Result<(), String> {
+        read_options(options).map(|_| ())
+    }
+
+    fn parse_with_options(input: &str, options: &Options) -> Result<Self::Input, ParseError> {
+        parse_gift_shop(input, options)
+    }
+
+    fn part_1(gift_shop: &Self::Input) -> Answer {
+        solve(&gift_shop.number_pairs, &gift_shop.rule(Part::One))
+    }
+
+    fn part_2(gift_shop: &Self::Input) -> Answer {
+        solve(&gift_shop.number_pairs, &gift_shop.rule(Part::Two))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solution::{Part, solve_example};
+    use crate::utils::random::Random;
+    const EXAMPLE: &str = "\
+11-22,95-115,998-1012,1188511880-1188511890,222220-222224,1698522-1698528,446443-446449,38593856-38593862,565653-565659,824824821-824824827,2121212118-2121212124
+";
+
+    #[test]
+    fn test_part_1_example() {
+        assert_eq!(
+            solve_example::<Puzzle>(EXAMPLE, Part::One),
+            Answer::Integer(1227775554)
+        );
+    }
+
+    #[test]
+    fn test_part_2_example() {
+        assert_eq!(
+            solve_example::<Puzzle>(EXAMPLE, Part::Two),
+            Answer::Integer(4174379265)
+        );
+    }
+
+    fn rule(repetitions: &str, min_block_length: u32) -> Rule {
+        Rule {
+            repetitions: repetitions.parse().unwrap(),
+            min_block_length,
+        }
+    }
+
+    /// Checks every ID of the range, splitting it into each possible number of blocks.
+    fn brute_force(from: i64, to: i64, rule: &Rule) -> Vec<i64> {
+        (from..=to)
+            .filter(|id| {
+                let id = id.to_string();
+                (2..=id.len()).any(|count| {
+                    id.len() % count == 0
+                        && rule.accepts(id.len() as u32, (id.len() / count) as u32)
+                        && id == id[..id.len() / count].repeat(count)
+                })
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_scan_matches_brute_force() {
+        let rules = [
+            rule("2", 1),
+            rule("2+", 1),
+            rule("2-3", 1),
+            rule("3,5", 1),
+            rule("2+", 2),
+        ];
+        let mut random = Random::new(12345);
+        let mut next = |bound: u64| random.below(bound) as i64;
+        for _ in 0..100 {
+            let from = next(1_000_000) + 1;
+            let to = from + next(5_000);
+            for rule in &rules {
+                let expected = brute_force(from, to, rule);
+                let tally = tally_invalid_ids(from, to, rule);
+                assert_eq!(
+                    tally.count,
+                    expected.len() as i64,
+                    "{}-{} {:?}",
+                    from,
+                    to,
+                    rule
+                );
+                assert_eq!(tally.sum, expected.iter().map(|&id| i128::from(id)).sum());
+                assert_eq!(list_invalid_ids(from, to, rule), expected);
+            }
+        }
+    }
+
+    #[test]
+    fn test_tally_wide_range() {
+        assert_eq!(
+            tally_invalid_ids(1, 999_999_999_999_999_999, &rule("2", 1)),
+            Tally {
+                count: 999_999_999,
+                sum: 495_495_495_540_950_040_450_040_950
+            }
+        );
+    }
+
+    #[test]
+    fn test_parse_repetitions() {
+        assert_eq!("3".parse(), Ok(Repetitions::Exactly(3)));
+        assert_eq!("2+".parse(), Ok(Repetitions::Between { min: 2, max: None }));
+        assert_eq!(
+            "2-4".parse(),
+            Ok(Repetitions::Between {
+                min: 2,
+                max: Some(4)
+            })
+        );
+        assert_eq!("2, 3,5".parse(), Ok(Repetitions::AnyOf(vec![2, 3, 5])));
+        assert!("1+".parse::<Repetitions>().is_err());
+        assert!("0".parse::<Repetitions>().is_err());
+        assert!("4-2".parse::<Repetitions>().is_err());
+        assert_eq!(
+            "3-3".parse(),
+            Ok(Repetitions::Between {
+                min: 3,
+                max: Some(3)
+            })
+        );
+        let mut options = Options::default();
+        options.set("repetitions", "4-2");
+        assert!(Puzzle::check_options(&options).is_err());
+        let mut options = Options::default();
+        options.set("min_block", "0");
+        assert!(Puzzle::check_options(&options).is_err());
+    }
+
+    #[test]
+    fn test_export_scan() {
+        let mut options = Options::default();
+        options.set("min_block", "2");
+        assert_eq!(
+            export_scan("11-22,1010-1313\n", &options, Part::Two, true),
+            Ok("\
+from,to,count,sum,ids
+11,22,0,0,
+1010,1313,4,4646,1010 1111 1212 1313
+"
+            .to_string())
+        );
+    }
 }
@@ -1,4 +1,6 @@
-use crate::utils;
+use crate::error::ParseError;
+use crate::solution::{Answer, Solution};
+use crate::utils::text::Text;
 use std::cell::RefCell;
 use std::collections::HashMap;
 
@@ -33,20 +35,88 @@ impl NumberFinder {
     }
 }
 
-fn parse_line(line: &str) -> Vec<i64> {
-    line.trim().chars().map(|c| c.to_string().parse::<i64>().unwrap()).collect()
+const MAX_DIGITS: u32 = 12;
+
+fn parse_line(line_index: usize, line: &str) -> Result<Vec<i64>, ParseError> {
+    let bank = line.trim();
+    if bank.len() < MAX_DIGITS as usize {
+        return Err(ParseError::at(
+            line_index,
+            line,
+            bank,
+            format!("expected a bank of at least {} batteries", MAX_DIGITS),
+        ));
+    }
+    bank.char_indices()
+        .map(|(i, c)| {
+            c.to_digit(10).map(i64::from).ok_or_else(|| {
+                ParseError::at(
+                    line_index,
+                    line,
+                    &bank[i..i + c.len_utf8()],
+                    "expected a digit",
+                )
+            })
+        })
+        .collect()
+}
+
+fn sum_highest_numbers(battery_banks: &[Vec<i64>], digits: u32) -> i64 {
+    battery_banks
+        .iter()
+        .map(|bank| NumberFinder::new(bank.clone()).find_highest_number(0, digits))
+        .sum()
+}
+
+pub struct Puzzle;
+
+impl Solution for Puzzle {
+    const DAY: usize = 3;
+    const TITLE: &'static str = "Lobby";
+    const INPUT_PATH: &'static str = "inputs/puzzle_3.txt";
+
+    type Input = Vec<Vec<i64>>;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        Text::new(input)
+            .lines()
+            .map(|(line_index, line)| parse_line(line_index, line))
+            .collect()
+    }
+
+    fn part_1(battery_banks: &Self::Input) -> Answer {
+        sum_highest_numbers(battery_banks, 2).into()
+    }
+
+    fn part_2(battery_banks: &Self::Input) -> Answer {
+        sum_highest_numbers(battery_banks, MAX_DIGITS).into()
+    }
 }
 
-pub fn solve_puzzle(input: &str) {
-    let lines = utils::extract_lines(input);
-    let battery_banks = lines.into_iter().map(parse_line).collect::<Vec<_>>();
-    let mut result_1 = 0;
-    let mut result_2 = 0;
-    for bank in battery_banks {
-        let finder = NumberFinder::new(bank.clone());
-        result_1 += finder.find_highest_number(0, 2);
-        result_2 += finder.find_highest_number(0, 12);
-    }
-    println!("Part 1: {}", result_1);
-    println!("Part 2: {}", result_2);
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solution::{Part, solve_example};
+    const EXAMPLE: &str = "\
+987654321111111
+811111111111119
+234234234234278
+818181911112111
+";
+
+    #[test]
+    fn test_part_1_example() {
+        assert_eq!(
+            solve_example::<Puzzle>(EXAMPLE, Part::One),
+            Answer::Integer(357)
+        );
+    }
+
+    #[test]
+    fn test_part_2_example() {
+        assert_eq!(
+            solve_example::<Puzzle>(EXAMPLE, Part::Two),
+            Answer::Integer(3121910778619)
+        );
+    }
 }
@@ -1,64 +1,40 @@
-fn count_neighbours(map: &Vec<Vec<bool>>, x: usize, y: usize) -> usize {
-    if !map[y][x] {
-        return 0;
-    }
-    let mut count = 0;
-    for dy in -1_i32..=1 {
-        let pos_y = y as i32 + dy;
-        if pos_y < 0 || pos_y >= map.len() as i32 {
-            continue;
-        }
-        let row = &map[pos_y as usize];
-        for dx in -1_i32..=1 {
-            if dx == 0 && dy == 0 {
-                continue;
-            }
-            let pos_x = x as i32 + dx;
-            if pos_x < 0 || pos_x >= row.len() as i32 {
-                continue;
-            }
-            if row[pos_x as usize] {
-                count += 1;
-            }
-        }
-    }
-    count
+use crate::error::ParseError;
+use crate::solution::{Answer, Solution};
+use crate::utils::grid::{Grid, Position};
+use crate::utils::text::Text;
+
+fn count_neighbours(map: &Grid<bool>, position: Position) -> usize {
+    map.neighbours_8(position)
+        .filter(|&neighbour| map[neighbour])
+        .count()
 }
 
-fn parse_input(input: &str) -> Vec<Vec<bool>> {
-    input
-        .trim()
-        .lines()
-        .map(|line| line.trim().chars().map(|c| c == '@').collect())
-        .collect()
+fn parse_input(input: &str) -> Result<Grid<bool>, ParseError> {
+    Text::new(input).grid("`@` or `.`", |c| match c {
+        '@' => Some(true),
+        '.' => Some(false),
+        _ => None,
+    })
 }
 
-fn solve_part_1(map: &Vec<Vec<bool>>) {
-    let mut result = 0;
-    for y in 0..map.len() {
-        for x in 0..map[y].len() {
-            if map[y][x] && count_neighbours(map, x, y) < 4 {
-                result += 1;
-            }
-        }
-    }
-    println!("Part 1: {}", result);
+fn solve_part_1(map: &Grid<bool>) -> usize {
+    map.find_all(|&paper| paper)
+        .filter(|&position| count_neighbours(map, position) < 4)
+        .count()
 }
 
-fn clear_accessible_spaces(map: &mut Vec<Vec<bool>>) -> usize {
+fn clear_accessible_spaces(map: &mut Grid<bool>) -> usize {
     let mut result = 0;
-    for y in 0..map.len() {
-        for x in 0..map[y].len() {
-            if map[y][x] && count_neighbours(map, x, y) < 4 {
-                result += 1;
-                map[y][x] = false;
-            }
+    for position in map.positions() {
+        if map[position] && count_neighbours(map, position) < 4 {
+            result += 1;
+            map[position] = false;
         }
     }
     result
 }
 
-fn solve_part_2(mut map: Vec<Vec<bool>>) {
+fn solve_part_2(mut map: Grid<bool>) -> usize {
     let mut total_result = 0;
     loop {
         let result = clear_accessible_spaces(&mut map);
@@ -67,11 +43,61 @@ fn solve_part_2(mut map: Vec<Vec<bool>>) {
         }
         total_result += result;
     }
-    println!("Part 2: {}", total_result);
+    total_result
+}
+
+pub struct Puzzle;
+
+impl Solution for Puzzle {
+    const DAY: usize = 4;
+    const TITLE: &'static str = "Printing Department";
+    const INPUT_PATH: &'static str = "inputs/puzzle_4.txt";
+
+    type Input = Grid<bool>;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        parse_input(input)
+    }
+
+    fn part_1(map: &Self::Input) -> Answer {
+        solve_part_1(map).into()
+    }
+
+    fn part_2(map: &Self::Input) -> Answer {
+        solve_part_2(map.clone()).into()
+    }
 }
 
-pub fn solve_puzzle(input: &str) {
-    let map = parse_input(input);
-    solve_part_1(&map);
-    solve_part_2(map);
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solution::{Part, solve_example};
+    const EXAMPLE: &str = "\
+..@@.@@@@.
+@@@.@.@.@@
+@@@@@.@.@@
+@.@@@@..@.
+@@.@@@@.@@
+.@@@@@@@.@
+.@.@.@.@@@
+@.@@@.@@@@
+.@@@@@@@@.
+@.@.@@@.@.
+";
+
+    #[test]
+    fn test_part_1_example() {
+        assert_eq!(
+            solve_example::<Puzzle>(EXAMPLE, Part::One),
+            Answer::Integer(13)
+        );
+    }
+
+    #[test]
+    fn test_part_2_example() {
+        assert_eq!(
+            solve_example::<Puzzle>(EXAMPLE, Part::Two),
+            Answer::Integer(43)
+        );
+    }
 }
@@ -1,3 +1,7 @@
+use crate::error::{ParseError, parse_number};
+use crate::solution::{Answer, Solution};
+use crate::utils::text::{NumberedLine, Text};
+
 #[derive(Clone, Debug, PartialEq)]
 struct Range {
     from: i64,
@@ -35,30 +39,44 @@ impl Range {
     }
 }
 
-fn get_input_blocks(input: &str) -> (Vec<&str>, Vec<&str>) {
-    let lines = input.lines().collect::<Vec<&str>>();
-    let split_position = lines.iter().position(|l| l.trim() == "").unwrap();
-    (
-        lines[..split_position].to_vec(),
-        lines[split_position + 1..].to_vec(),
-    )
+fn get_input_blocks<'t>(
+    text: &'t Text,
+) -> Result<(&'t [NumberedLine<'t>], &'t [NumberedLine<'t>]), ParseError> {
+    match text.blocks()[..] {
+        [ranges, ingredients] => Ok((ranges, ingredients)),
+        [_, _, extra, ..] => Err(ParseError::at(
+            extra[0].0,
+            extra[0].1,
+            extra[0].1,
+            "expected nothing after the ingredient IDs",
+        )),
+        _ => Err(ParseError::at_end(
+            text.line_count(),
+            "expected a blank line between the ID ranges and the ingredient IDs",
+        )),
+    }
 }
 
-fn get_number_pairs(input: Vec<&str>) -> Vec<Range> {
+fn get_number_pairs(input: &[NumberedLine]) -> Result<Vec<Range>, ParseError> {
     input
         .iter()
-        .map(|id_pair| {
-            let id_pair = id_pair.trim().split_once("-").unwrap();
-            let (id1, id2) = id_pair;
-            Range::new(id1.parse::<i64>().unwrap(), id2.parse::<i64>().unwrap())
+        .map(|&(line_index, line)| {
+            let id_pair = line.trim();
+            let (id1, id2) = id_pair.split_once("-").ok_or_else(|| {
+                ParseError::at(line_index, line, id_pair, "expected an ID range like `3-5`")
+            })?;
+            Ok(Range::new(
+                parse_number(line_index, line, id1)?,
+                parse_number(line_index, line, id2)?,
+            ))
         })
         .collect()
 }
 
-fn get_numbers(input: Vec<&str>) -> Vec<i64> {
+fn get_numbers(input: &[NumberedLine]) -> Result<Vec<i64>, ParseError> {
     input
         .iter()
-        .map(|id_pair| id_pair.parse::<i64>().unwrap())
+        .map(|&(line_index, line)| parse_number(line_index, line, line))
         .collect()
 }
 
@@ -66,23 +84,18 @@ fn is_in_any_range(item: i64, ranges: &[Range]) -> bool {
     ranges.iter().any(|range| range.contains(item))
 }
 
-pub fn solve_puzzle(input: &str) {
-    let (ranges, ingredients) = get_input_blocks(input);
-    let ranges = get_number_pairs(ranges);
-    let ingredients = get_numbers(ingredients);
-
-    let mut count = 0;
-    for ingredient in ingredients {
-        if is_in_any_range(ingredient, &ranges) {
-            count += 1;
-        }
-    }
-    println!("Part 1: {}", count);
+fn count_fresh_ingredients(ingredients: &[i64], ranges: &[Range]) -> usize {
+    ingredients
+        .iter()
+        .filter(|&&ingredient| is_in_any_range(ingredient, ranges))
+        .count()
+}
 
-    let mut trimmed_ranges = vec![ranges[0].clone()];
+fn count_fresh_ids(ranges: &[Range]) -> i64 {
+    let mut trimmed_ranges: Vec<Range> = vec![];
 
-    for range_to_trim in ranges.into_iter().skip(1) {
-        let mut intermediate_ranges = vec![range_to_trim];
+    for range_to_trim in ranges.iter() {
+        let mut intermediate_ranges = vec![range_to_trim.clone()];
         for range in trimmed_ranges.iter() {
             intermediate_ranges = intermediate_ranges
                 .into_iter()
@@ -92,15 +105,85 @@ pub fn solve_puzzle(input: &str) {
         trimmed_ranges.extend(intermediate_ranges);
     }
 
-    let total_len = trimmed_ranges
+    trimmed_ranges
         .iter()
-        .fold(0, |acc, range| acc + range.len());
-    println!("Part 2: {}", total_len);
+        .fold(0, |acc, range| acc + range.len())
+}
+
+pub struct Inventory {
+    ranges: Vec<Range>,
+    ingredients: Vec<i64>,
+}
+
+pub struct Puzzle;
+
+impl Solution for Puzzle {
+    const DAY: usize = 5;
+    const TITLE: &'static str = "Cafeteria";
+    const INPUT_PATH: &'static str = "inputs/puzzle_5.txt";
+
+    type Input = Inventory;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        let text = Text::new(input);
+        let (ranges, ingredients) = get_input_blocks(&text)?;
+        Ok(Inventory {
+            ranges: get_number_pairs(ranges)?,
+            ingredients: get_numbers(ingredients)?,
+        })
+    }
+
+    fn part_1(inventory: &Self::Input) -> Answer {
+        count_fresh_ingredients(&inventory.ingredients, &inventory.ranges).into()
+    }
+
+    fn part_2(inventory: &Self::Input) -> Answer {
+        count_fresh_ids(&inventory.ranges).into()
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::solution::{Part, solve_example};
+    const EXAMPLE: &str = "\
+3-5
+10-14
+16-20
+12-18
+
+1
+5
+8
+11
+17
+32
+";
+
+    #[test]
+    fn test_part_1_example() {
+        assert_eq!(
+            solve_example::<Puzzle>(EXAMPLE, Part::One),
+            Answer::Integer(3)
+        );
+    }
+
+    #[test]
+    fn test_part_2_example() {
+        assert_eq!(
+            solve_example::<Puzzle>(EXAMPLE, Part::Two),
+            Answer::Integer(14)
+        );
+    }
+
+    #[test]
+    fn test_part_1_example_with_crlf_and_blank_lines() {
+        let example = format!("\r\n{}\r\n\r\n", EXAMPLE.replace('\n', "  \r\n"));
+        assert_eq!(
+            solve_example::<Puzzle>(&example, Part::One),
+            Answer::Integer(3)
+        );
+    }
 
     #[test]
     fn test_range_trim_range_to_not_overlap() {
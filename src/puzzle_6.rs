@@ -1,6 +1,9 @@
-use crate::utils::transpose;
+use crate::error::{ParseError, parse_number};
+use crate::solution::{Answer, Solution};
+use crate::utils::text::Text;
+use crate::utils::{transpose, transpose_padded};
 
-#[derive(Debug, PartialEq)]
+#[derive(Clone, Copy, Debug, PartialEq)]
 enum Operation {
     Add,
     Multiply,
@@ -13,14 +16,17 @@ impl Operation {
             Operation::Multiply => numbers.iter().product(),
         }
     }
-}
 
-impl Into<Operation> for &str {
-    fn into(self) -> Operation {
-        match self {
-            "+" => Operation::Add,
-            "*" => Operation::Multiply,
-            _ => panic!("Invalid operation"),
+    fn parse(line_index: usize, line: &str, token: &str) -> Result<Self, ParseError> {
+        match token {
+            "+" => Ok(Operation::Add),
+            "*" => Ok(Operation::Multiply),
+            _ => Err(ParseError::at(
+                line_index,
+                line,
+                token,
+                "expected `+` or `*`",
+            )),
         }
     }
 }
@@ -31,53 +37,79 @@ struct Numbers {
     operation: Operation,
 }
 
-fn parse_input_rows<'t>(lines: &Vec<&'t str>) -> (Vec<&'t str>, &'t str) {
-    let number_rows = lines
-        .iter()
-        .take(lines.len() - 1)
-        .map(|s| *s)
-        .collect::<Vec<_>>();
-    let operation_row = lines.iter().last().unwrap();
-    (number_rows, operation_row)
+pub struct Worksheet {
+    number_rows: Vec<String>,
+    operations: Vec<Operation>,
+}
+
+fn parse_input_rows(text: &Text) -> Result<Worksheet, ParseError> {
+    let lines = text.lines().collect::<Vec<_>>();
+    let Some((&(operation_line_index, operation_row), number_rows)) = lines.split_last() else {
+        return Err(ParseError::at_end(
+            text.line_count(),
+            "expected rows of numbers and a row of operations",
+        ));
+    };
+    let operations = operation_row
+        .split_whitespace()
+        .map(|op| Operation::parse(operation_line_index, operation_row, op))
+        .collect::<Result<Vec<_>, _>>()?;
+    for &(line_index, row) in number_rows {
+        let numbers = row.split_whitespace().collect::<Vec<_>>();
+        for number in numbers.iter() {
+            parse_number::<i64>(line_index, row, number)?;
+        }
+        if numbers.len() != operations.len() {
+            return Err(ParseError::at(
+                line_index,
+                row,
+                row,
+                format!("expected {} numbers, one per operation", operations.len()),
+            ));
+        }
+    }
+    Ok(Worksheet {
+        number_rows: number_rows.iter().map(|(_, row)| row.to_string()).collect(),
+        operations,
+    })
 }
 
-fn parse_part_1_numbers(number_rows: &Vec<&str>, operation_row: &str) -> Vec<Numbers> {
+fn parse_part_1_numbers(number_rows: &[String], operations: &[Operation]) -> Vec<Numbers> {
     let numbers = transpose(
-        number_rows
-            .into_iter()
+        &number_rows
+            .iter()
             .map(|row| {
                 row.split_whitespace()
                     .map(|n| n.parse::<i64>().unwrap())
                     .collect::<Vec<_>>()
             })
             .collect::<Vec<_>>(),
-    );
-    let operations = operation_row
-        .split_whitespace()
-        .map(|op| op.into())
-        .collect::<Vec<Operation>>();
+    )
+    .expect("every row has one number per operation");
     operations
-        .into_iter()
+        .iter()
         .zip(numbers)
-        .map(|(op, numbers)| Numbers {
+        .map(|(&op, numbers)| Numbers {
             numbers,
             operation: op,
         })
         .collect()
 }
 
-fn parse_part_2_numbers(number_rows: &Vec<&str>, operation_row: &str) -> Vec<Numbers> {
-    let character_rows = transpose(
-        number_rows
+fn parse_part_2_numbers(number_rows: &[String], operations: &[Operation]) -> Vec<Numbers> {
+    // Rows end at their last digit, so the shorter ones are padded with blanks.
+    let character_rows = transpose_padded(
+        &number_rows
             .iter()
             .map(|c| c.chars().collect::<Vec<_>>())
             .collect::<Vec<_>>(),
+        ' ',
     );
     let digit_rows = character_rows
         .into_iter()
         .map(|row| {
             row.iter()
-                .filter(|c| c.is_digit(10))
+                .filter(|c| c.is_ascii_digit())
                 .map(|c| c.to_digit(10).unwrap() as i64)
                 .collect::<Vec<_>>()
         })
@@ -102,35 +134,72 @@ fn parse_part_2_numbers(number_rows: &Vec<&str>, operation_row: &str) -> Vec<Num
         acc.last_mut().unwrap().push(x);
         acc
     });
-    let operations = operation_row
-        .split_whitespace()
-        .map(|op| op.into())
-        .collect::<Vec<Operation>>();
     operations
-        .into_iter()
+        .iter()
         .zip(number_groups)
-        .map(|(op, numbers)| Numbers {
+        .map(|(&op, numbers)| Numbers {
             numbers,
             operation: op,
         })
         .collect()
 }
 
-fn solve_part_1(number_rows: &Vec<&str>, operation_row: &str) {
-    let numbers = parse_part_1_numbers(number_rows, operation_row);
-    let part_1: i64 = numbers.iter().map(|n| n.operation.apply(&n.numbers)).sum();
-    println!("Part 1: {}", part_1);
+fn solve_part_1(worksheet: &Worksheet) -> i64 {
+    let numbers = parse_part_1_numbers(&worksheet.number_rows, &worksheet.operations);
+    numbers.iter().map(|n| n.operation.apply(&n.numbers)).sum()
+}
+
+fn solve_part_2(worksheet: &Worksheet) -> i64 {
+    let numbers = parse_part_2_numbers(&worksheet.number_rows, &worksheet.operations);
+    numbers.iter().map(|n| n.operation.apply(&n.numbers)).sum()
 }
 
-fn solve_part_2(number_rows: &Vec<&str>, operation_row: &str) {
-    let numbers = parse_part_2_numbers(number_rows, operation_row);
-    let part_1: i64 = numbers.iter().map(|n| n.operation.apply(&n.numbers)).sum();
-    println!("Part 2: {}", part_1);
+pub struct Puzzle;
+
+impl Solution for Puzzle {
+    const DAY: usize = 6;
+    const TITLE: &'static str = "Trash Compactor";
+    const INPUT_PATH: &'static str = "inputs/puzzle_6.txt";
+
+    type Input = Worksheet;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        parse_input_rows(&Text::new(input))
+    }
+
+    fn part_1(worksheet: &Self::Input) -> Answer {
+        solve_part_1(worksheet).into()
+    }
+
+    fn part_2(worksheet: &Self::Input) -> Answer {
+        solve_part_2(worksheet).into()
+    }
 }
 
-pub fn solve_puzzle(input: &str) {
-    let lines = input.lines().collect();
-    let (number_rows, operation_row) = parse_input_rows(&lines);
-    solve_part_1(&number_rows, operation_row);
-    solve_part_2(&number_rows, operation_row);
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solution::{Part, solve_example};
+    const EXAMPLE: &str = concat!(
+        "123 328  51 64 \n",
+        " 45 64  387 23 \n",
+        "  6 98  215 314\n",
+        "*   +   *   +  \n",
+    );
+
+    #[test]
+    fn test_part_1_example() {
+        assert_eq!(
+            solve_example::<Puzzle>(EXAMPLE, Part::One),
+            Answer::Integer(4277556)
+        );
+    }
+
+    #[test]
+    fn test_part_2_example() {
+        assert_eq!(
+            solve_example::<Puzzle>(EXAMPLE, Part::Two),
+            Answer::Integer(3263827)
+        );
+    }
 }
@@ -1,19 +1,23 @@
+use crate::error::ParseError;
+use crate::solution::{Answer, Solution};
+use crate::utils::grid::{Grid, Position};
+use crate::utils::text::Text;
 use std::collections::{HashMap, HashSet};
 
 #[derive(Debug, PartialEq)]
-enum ManifoldEntry {
+pub enum ManifoldEntry {
     Empty,
     Emitter,
     Splitter,
 }
 
 struct BeamSimulation<'t> {
-    manifold: &'t Vec<Vec<ManifoldEntry>>,
+    manifold: &'t Grid<ManifoldEntry>,
     steps: Vec<BeamSimulationStep>,
 }
 
 impl<'t> BeamSimulation<'t> {
-    fn new(manifold: &'t Vec<Vec<ManifoldEntry>>, (emitter_x, emitter_y): (usize, usize)) -> Self {
+    fn new(manifold: &'t Grid<ManifoldEntry>, (emitter_x, emitter_y): Position) -> Self {
         let first_step = BeamSimulationStep::new(emitter_y, HashSet::from([emitter_x]));
         Self {
             manifold,
@@ -28,10 +32,10 @@ impl<'t> BeamSimulation<'t> {
     fn simulate_until_end(&mut self) {
         loop {
             let step = self.steps.last().unwrap();
-            if step.y >= self.manifold.len() {
+            if step.y >= self.manifold.height() {
                 break;
             }
-            self.steps.push(step.simulate_tick(&self.manifold));
+            self.steps.push(step.simulate_tick(self.manifold));
         }
     }
 }
@@ -51,17 +55,17 @@ impl BeamSimulationStep {
         }
     }
 
-    fn simulate_tick(&self, manifold: &Vec<Vec<ManifoldEntry>>) -> Self {
+    fn simulate_tick(&self, manifold: &Grid<ManifoldEntry>) -> Self {
         let mut new_beams_x = HashSet::new();
-        let manifold_row = &manifold[self.y];
         let mut split_count = 0;
-        for x in self.beams_x.iter() {
-            if manifold_row[*x] == ManifoldEntry::Splitter {
+        for &x in self.beams_x.iter() {
+            if manifold.get((x, self.y)) == Some(&ManifoldEntry::Splitter) {
                 split_count += 1;
-                new_beams_x.insert(*x + 1);
-                new_beams_x.insert(*x - 1);
+                // Beams split off the side of the manifold leave it.
+                new_beams_x.extend(x.checked_sub(1));
+                new_beams_x.extend(Some(x + 1).filter(|&x| x < manifold.width()));
             } else {
-                new_beams_x.insert(*x);
+                new_beams_x.insert(x);
             }
         }
         Self {
@@ -72,50 +76,42 @@ impl BeamSimulationStep {
     }
 }
 
-fn find_emitter_position(manifold: &Vec<Vec<ManifoldEntry>>) -> Option<(usize, usize)> {
-    for (y, row) in manifold.iter().enumerate() {
-        for (x, entry) in row.iter().enumerate() {
-            if *entry == ManifoldEntry::Emitter {
-                return Some((x, y));
-            }
-        }
-    }
-    None
+fn find_emitter_position(manifold: &Grid<ManifoldEntry>) -> Option<Position> {
+    manifold.find(|entry| *entry == ManifoldEntry::Emitter)
 }
 
-fn parse_manifold(input: &str) -> Vec<Vec<ManifoldEntry>> {
-    input
-        .trim()
-        .lines()
-        .map(|line| {
-            line.trim()
-                .chars()
-                .map(|c| match c {
-                    'S' => ManifoldEntry::Emitter,
-                    '^' => ManifoldEntry::Splitter,
-                    _ => ManifoldEntry::Empty,
-                })
-                .collect()
-        })
-        .collect()
+fn parse_manifold(input: &str) -> Result<Grid<ManifoldEntry>, ParseError> {
+    let text = Text::new(input);
+    let manifold = text.grid("`S`, `^` or `.`", |c| match c {
+        'S' => Some(ManifoldEntry::Emitter),
+        '^' => Some(ManifoldEntry::Splitter),
+        '.' => Some(ManifoldEntry::Empty),
+        _ => None,
+    })?;
+    if find_emitter_position(&manifold).is_none() {
+        return Err(ParseError::at_end(
+            text.line_count(),
+            "expected the manifold to contain an emitter `S`",
+        ));
+    }
+    Ok(manifold)
 }
 
 fn simulate_beam(
-    manifold: &Vec<Vec<ManifoldEntry>>,
-    (beam_x, beam_y): (usize, usize),
-    previous_paths: &mut HashMap<(usize, usize), usize>,
+    manifold: &Grid<ManifoldEntry>,
+    (beam_x, beam_y): Position,
+    previous_paths: &mut HashMap<Position, usize>,
 ) -> usize {
-    if beam_y >= manifold.len() {
-        return 1;
-    }
-    let row = &manifold[beam_y];
-    if beam_x >= row.len() {
+    if beam_y >= manifold.height() {
         return 1;
     }
+    // A beam split off the side of the manifold leaves it without reaching the bottom.
+    let Some(entry) = manifold.get((beam_x, beam_y)) else {
+        return 0;
+    };
     if previous_paths.contains_key(&(beam_x, beam_y)) {
         return *previous_paths.get(&(beam_x, beam_y)).unwrap();
     }
-    let entry = &row[beam_x];
     let result = match entry {
         ManifoldEntry::Splitter => {
             let right = simulate_beam(manifold, (beam_x + 1, beam_y + 1), previous_paths);
@@ -132,15 +128,83 @@ fn simulate_beam(
     result
 }
 
-pub fn solve_puzzle(input: &str) {
-    let manifold = parse_manifold(input);
-    let (emitter_x, emitter_y) = find_emitter_position(&manifold).unwrap();
-    let mut simulation = BeamSimulation::new(&manifold, (emitter_x, emitter_y));
-    simulation.simulate_until_end();
-    println!("Part 1: {}", simulation.get_split_count());
-
-    let mut previous_paths = HashMap::new();
-    let beam_split_count =
-        simulate_beam(&manifold, (emitter_x, emitter_y + 1), &mut previous_paths);
-    println!("Part 2: {}", beam_split_count);
+pub struct Puzzle;
+
+impl Solution for Puzzle {
+    const DAY: usize = 7;
+    const TITLE: &'static str = "Laboratories";
+    const INPUT_PATH: &'static str = "inputs/puzzle_7.txt";
+
+    type Input = Grid<ManifoldEntry>;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        parse_manifold(input)
+    }
+
+    fn part_1(manifold: &Self::Input) -> Answer {
+        let emitter_position = find_emitter_position(manifold).unwrap();
+        let mut simulation = BeamSimulation::new(manifold, emitter_position);
+        simulation.simulate_until_end();
+        simulation.get_split_count().into()
+    }
+
+    fn part_2(manifold: &Self::Input) -> Answer {
+        let (emitter_x, emitter_y) = find_emitter_position(manifold).unwrap();
+        let mut previous_paths = HashMap::new();
+        simulate_beam(manifold, (emitter_x, emitter_y + 1), &mut previous_paths).into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solution::{Part, solve_example};
+    const EXAMPLE: &str = "\
+.......S.......
+...............
+.......^.......
+...............
+......^.^......
+...............
+.....^.^.^.....
+...............
+....^.^...^....
+...............
+...^.^...^.^...
+...............
+..^...^.....^..
+...............
+.^.^.^.^.^...^.
+...............
+";
+
+    #[test]
+    fn test_part_1_example() {
+        assert_eq!(
+            solve_example::<Puzzle>(EXAMPLE, Part::One),
+            Answer::Integer(21)
+        );
+    }
+
+    #[test]
+    fn test_part_2_example() {
+        assert_eq!(
+            solve_example::<Puzzle>(EXAMPLE, Part::Two),
+            Answer::Integer(40)
+        );
+    }
+
+    #[test]
+    fn test_splitters_in_the_edge_columns() {
+        for manifold in [".S\n.^\n..\n", "S.\n^.\n..\n"] {
+            assert_eq!(
+                solve_example::<Puzzle>(manifold, Part::One),
+                Answer::Integer(1)
+            );
+            assert_eq!(
+                solve_example::<Puzzle>(manifold, Part::Two),
+                Answer::Integer(1)
+            );
+        }
+    }
 }
@@ -1,8 +1,17 @@
-use std::collections::HashSet;
-use std::hash::{Hash, Hasher};
+use crate::error::{ParseError, parse_number};
+use crate::solution::{Answer, Options, Solution};
+use crate::utils::disjoint_set::DisjointSet;
+use crate::utils::kd_tree::KdTree;
+use crate::utils::metric::Metric;
+use crate::utils::text::Text;
+use std::str::FromStr;
+
+/// The largest coordinate accepted, which keeps squared distances within a `u64` and the part 2
+/// product within an `i64` answer.
+const MAX_COORDINATE: u64 = i32::MAX as u64;
 
 #[derive(Debug, Eq, PartialEq)]
-struct JunctionBox {
+pub struct JunctionBox {
     x: u64,
     y: u64,
     z: u64,
@@ -13,138 +22,415 @@ impl JunctionBox {
         Self { x, y, z }
     }
 
-    fn distance_to(&self, other: &Self) -> u64 {
-        self.x.abs_diff(other.x).pow(2)
-            + self.y.abs_diff(other.y).pow(2)
-            + self.z.abs_diff(other.z).pow(2)
+    fn coordinates(&self) -> [u64; 3] {
+        [self.x, self.y, self.z]
     }
 
-    fn parse(input: &str) -> Self {
-        let components = input
+    fn parse(line_index: usize, line: &str) -> Result<Self, ParseError> {
+        let components = line
             .trim()
             .split(',')
-            .map(|v| v.parse::<u64>().unwrap())
-            .collect::<Vec<_>>();
+            .map(|v| {
+                let coordinate = parse_number::<u64>(line_index, line, v)?;
+                if coordinate > MAX_COORDINATE {
+                    return Err(ParseError::at(
+                        line_index,
+                        line,
+                        v.trim(),
+                        format!("expected a coordinate of at most {}", MAX_COORDINATE),
+                    ));
+                }
+                Ok(coordinate)
+            })
+            .collect::<Result<Vec<_>, _>>()?;
         if components.len() != 3 {
-            panic!("Invalid input");
+            return Err(ParseError::at(
+                line_index,
+                line,
+                line.trim(),
+                "expected three coordinates like `162,817,812`",
+            ));
         }
-        Self::new(components[0], components[1], components[2])
+        Ok(Self::new(components[0], components[1], components[2]))
     }
 }
 
-impl<'a> Hash for &'a JunctionBox {
-    fn hash<H: Hasher>(&self, state: &mut H) {
-        // Use std::ptr::hash to hash the reference by its address.
-        // `*self` is the &Position, and we pass it to the function.
-        std::ptr::hash(*self, state);
+fn parse_junction_boxes(input: &str) -> Result<Vec<JunctionBox>, ParseError> {
+    let text = Text::new(input);
+    let junction_boxes = text
+        .lines()
+        .map(|(line_index, line)| JunctionBox::parse(line_index, line))
+        .collect::<Result<Vec<_>, _>>()?;
+    if junction_boxes.len() < 2 {
+        return Err(ParseError::at_end(
+            text.line_count(),
+            "expected at least two junction boxes to connect",
+        ));
     }
+    Ok(junction_boxes)
 }
 
-fn parse_junction_boxes(input: &str) -> Vec<JunctionBox> {
-    input
-        .trim()
-        .lines()
-        .map(|line| JunctionBox::parse(line))
-        .collect()
+fn build_tree(junction_boxes: &[JunctionBox], metric: Metric) -> KdTree<3> {
+    KdTree::new(
+        junction_boxes
+            .iter()
+            .map(JunctionBox::coordinates)
+            .collect(),
+        metric,
+    )
 }
 
-#[derive(Debug)]
-struct Circuit<'t> {
-    junction_boxes: HashSet<&'t JunctionBox>,
+fn solve_part_1(junction_boxes: &[JunctionBox], metric: Metric, connection_count: usize) -> i64 {
+    let tree = build_tree(junction_boxes, metric);
+    let mut circuits = DisjointSet::new(junction_boxes.len());
+    for (_, source, destination) in tree.pairs_by_distance().take(connection_count) {
+        circuits.union(source, destination);
+    }
+    let mut sizes = circuits.component_sizes();
+    sizes.sort_by(|size1, size2| size2.cmp(size1));
+    sizes.into_iter().take(3).map(|size| size as i64).product()
 }
 
-impl<'t> Circuit<'t> {
-    fn new() -> Self {
-        Self {
-            junction_boxes: HashSet::new(),
-        }
+/// A connection between the junction boxes at two indices.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Connection {
+    pub from: usize,
+    pub to: usize,
+    pub distance: u64,
+}
+
+/// The connections that join all junction boxes into one circuit with the least total length,
+/// in the order they are made when always connecting the closest pair that is not yet connected.
+/// When only the closest pairs are considered, these are the connections of the circuits they
+/// form instead.
+#[derive(Debug, PartialEq)]
+pub struct SpanningTree {
+    pub connections: Vec<Connection>,
+    junction_box_count: usize,
+}
+
+impl SpanningTree {
+    pub fn total_distance(&self) -> u64 {
+        self.connections
+            .iter()
+            .map(|connection| connection.distance)
+            .sum()
     }
 
-    fn add(&mut self, jb: &'t JunctionBox) {
-        self.junction_boxes.insert(jb);
+    /// The connection that joins the last two circuits into one, if they were joined.
+    pub fn completing_connection(&self) -> Option<&Connection> {
+        self.connections
+            .last()
+            .filter(|_| self.connections.len() + 1 == self.junction_box_count)
     }
 
-    fn contains(&self, jb: &JunctionBox) -> bool {
-        self.junction_boxes.contains(&jb)
+    /// Writes one connection per line, with the coordinates of both junction boxes.
+    pub fn to_csv(&self, junction_boxes: &[JunctionBox]) -> String {
+        let mut csv = "from,to,from_x,from_y,from_z,to_x,to_y,to_z,distance\n".to_string();
+        for connection in &self.connections {
+            let (from, to) = (
+                &junction_boxes[connection.from],
+                &junction_boxes[connection.to],
+            );
+            csv.push_str(&format!(
+                "{},{},{},{},{},{},{},{},{}\n",
+                connection.from,
+                connection.to,
+                from.x,
+                from.y,
+                from.z,
+                to.x,
+                to.y,
+                to.z,
+                connection.distance
+            ));
+        }
+        csv
     }
 
-    fn join_with(&mut self, other: Self) {
-        self.junction_boxes.extend(other.junction_boxes);
+    /// Writes the tree as a Graphviz graph, highlighting the completing connection.
+    pub fn to_dot(&self, junction_boxes: &[JunctionBox]) -> String {
+        let mut dot = format!(
+            "graph circuits {{\n  label=\"total distance {}\";\n",
+            self.total_distance()
+        );
+        for (index, junction_box) in junction_boxes.iter().enumerate() {
+            dot.push_str(&format!(
+                "  {} [label=\"{},{},{}\"];\n",
+                index, junction_box.x, junction_box.y, junction_box.z
+            ));
+        }
+        let completing_connection = self.completing_connection();
+        for connection in &self.connections {
+            let style = if Some(connection) == completing_connection {
+                ", color=red, penwidth=2"
+            } else {
+                ""
+            };
+            dot.push_str(&format!(
+                "  {} -- {} [label=\"{}\"{}];\n",
+                connection.from, connection.to, connection.distance, style
+            ));
+        }
+        dot.push_str("}\n");
+        dot
     }
 }
 
-struct CircuitManager<'t> {
-    circuits: Vec<Circuit<'t>>,
+/// Connects the junction boxes, considering only the `pair_limit` closest pairs when it is set.
+pub fn minimum_spanning_tree(
+    junction_boxes: &[JunctionBox],
+    metric: Metric,
+    pair_limit: Option<usize>,
+) -> SpanningTree {
+    let tree = build_tree(junction_boxes, metric);
+    let mut circuits = DisjointSet::new(junction_boxes.len());
+    let mut connections = Vec::new();
+    let pairs = tree.pairs_by_distance();
+    for (distance, from, to) in pairs.take(pair_limit.unwrap_or(usize::MAX)) {
+        if circuits.component_count() == 1 {
+            break;
+        }
+        if circuits.union(from, to) {
+            connections.push(Connection { from, to, distance });
+        }
+    }
+    SpanningTree {
+        connections,
+        junction_box_count: junction_boxes.len(),
+    }
+}
+
+fn solve_part_2(junction_boxes: &[JunctionBox], metric: Metric) -> Option<u64> {
+    let connection =
+        *minimum_spanning_tree(junction_boxes, metric, None).completing_connection()?;
+    Some(junction_boxes[connection.from].x * junction_boxes[connection.to].x)
+}
+
+/// The ways [`SpanningTree`] can be written out.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum TreeFormat {
+    Csv,
+    Dot,
 }
 
-impl<'t> CircuitManager<'t> {
-    fn new(junction_boxes: &'t Vec<JunctionBox>) -> Self {
-        Self {
-            circuits: junction_boxes
-                .iter()
-                .map(|jb| {
-                    let mut circuit = Circuit::new();
-                    circuit.add(jb);
-                    circuit
-                })
-                .collect(),
+impl FromStr for TreeFormat {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "csv" => Ok(TreeFormat::Csv),
+            "dot" => Ok(TreeFormat::Dot),
+            _ => Err(format!("unknown tree format `{}`", value)),
         }
     }
+}
 
-    fn connect_junction_boxes(&mut self, from: &'t JunctionBox, to: &'t JunctionBox) {
-        let mut from_circuit = self.remove_circuit_containing_junction_box(from);
-        if from_circuit.contains(to) {
-            self.circuits.push(from_circuit);
-            return;
-        }
-        let to_circuit = self.remove_circuit_containing_junction_box(to);
-        from_circuit.join_with(to_circuit);
-        self.circuits.push(from_circuit);
+/// Computes the minimum spanning tree of a puzzle input, with the metric and connection count of
+/// checked `options`, and renders it in `format`.
+pub fn export_spanning_tree(
+    input: &str,
+    options: &Options,
+    format: TreeFormat,
+) -> Result<String, ParseError> {
+    let (metric, connection_count) =
+        read_options(options).expect("options are checked before exporting");
+    let junction_boxes = parse_junction_boxes(input)?;
+    let spanning_tree = minimum_spanning_tree(&junction_boxes, metric, connection_count);
+    Ok(match format {
+        TreeFormat::Csv => spanning_tree.to_csv(&junction_boxes),
+        TreeFormat::Dot => spanning_tree.to_dot(&junction_boxes),
+    })
+}
+
+pub struct Playground {
+    junction_boxes: Vec<JunctionBox>,
+    metric: Metric,
+    /// The number of closest pairs connected in part 1, all junction boxes by default.
+    connection_count: Option<usize>,
+}
+
+fn read_options(options: &Options) -> Result<(Metric, Option<usize>), String> {
+    options.check_keys(&["metric", "connections"])?;
+    Ok((
+        options.get("metric")?.unwrap_or_default(),
+        options.get("connections")?,
+    ))
+}
+
+pub struct Puzzle;
+
+impl Solution for Puzzle {
+    const DAY: usize = 8;
+    const TITLE: &'static str = "Playground";
+    const INPUT_PATH: &'static str = "inputs/puzzle_8.txt";
+
+    type Input = Playground;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        Self::parse_with_options(input, &Options::default())
     }
 
-    fn remove_circuit_containing_junction_box(&mut self, jb: &'t JunctionBox) -> Circuit<'t> {
-        let index = self
-            .circuits
-            .iter()
-            .position(|circuit| circuit.contains(jb))
-            .unwrap();
-        self.circuits.remove(index)
+    fn check_options(options: &Options) -> Result<(), String> {
+        read_options(options).map(|_| ())
     }
-}
 
-pub fn solve_puzzle(input: &str) {
-    let junction_boxes = parse_junction_boxes(input);
-    let mut connections = Vec::<(u64, &JunctionBox, &JunctionBox)>::new();
-    for (i, jb1) in junction_boxes.iter().enumerate() {
-        for jb2 in junction_boxes.iter().skip(i + 1) {
-            let distance = jb1.distance_to(jb2);
-            connections.push((distance, jb1, jb2));
-        }
+    fn parse_with_options(input: &str, options: &Options) -> Result<Self::Input, ParseError> {
+        let (metric, connection_count) =
+            read_options(options).expect("options are checked before parsing");
+        Ok(Playground {
+            junction_boxes: parse_junction_boxes(input)?,
+            metric,
+            connection_count,
+        })
     }
-    connections.sort_by(|(dist1, _, _), (dist2, _, _)| dist1.cmp(dist2));
-    let mut manager = CircuitManager::new(&junction_boxes);
-    for (_, source, destination) in connections.iter().take(junction_boxes.len()) {
-        manager.connect_junction_boxes(source, destination);
-    }
-    let mut sorted_circuits = manager
-        .circuits
-        .iter()
-        .map(|circuit| (circuit.junction_boxes.len(), circuit))
-        .collect::<Vec<_>>();
-    sorted_circuits.sort_by(|(size1, _), (size2, _)| size2.cmp(size1));
-    let result: i64 = sorted_circuits
-        .into_iter()
-        .take(3)
-        .map(|(size, _)| size as i64)
-        .product();
-    println!("Part 1: {}", result);
-
-    let mut manager = CircuitManager::new(&junction_boxes);
-    'l: for (_, source, destination) in connections.into_iter() {
-        manager.connect_junction_boxes(source, destination);
-        if manager.circuits.len() == 1 {
-            println!("Part 2: {}", source.x * destination.x);
-            break 'l;
+
+    fn part_1(playground: &Self::Input) -> Answer {
+        let junction_boxes = &playground.junction_boxes;
+        let connection_count = playground.connection_count.unwrap_or(junction_boxes.len());
+        solve_part_1(junction_boxes, playground.metric, connection_count).into()
+    }
+
+    fn part_2(playground: &Self::Input) -> Answer {
+        match solve_part_2(&playground.junction_boxes, playground.metric) {
+            Some(product) => product.into(),
+            None => Answer::Text("no connection completes the circuit".to_string()),
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solution::{Part, solve_example};
+    const EXAMPLE: &str = "\
+162,817,812
+57,618,57
+906,360,560
+592,479,940
+352,342,300
+466,668,158
+542,29,236
+431,825,988
+739,650,466
+52,470,668
+216,146,977
+819,987,18
+117,168,530
+805,96,715
+346,949,466
+970,615,88
+941,993,340
+862,61,35
+984,92,344
+425,690,689
+";
+
+    #[test]
+    fn test_part_1_example() {
+        let mut options = Options::default();
+        options.set("connections", "10");
+        let playground = Puzzle::parse_with_options(EXAMPLE, &options).unwrap();
+        assert_eq!(Puzzle::part_1(&playground), Answer::Integer(40));
+    }
+
+    #[test]
+    fn test_check_options() {
+        let mut options = Options::default();
+        options.set("metric", "manhattan");
+        assert_eq!(Puzzle::check_options(&options), Ok(()));
+        options.set("metric", "hamming");
+        assert!(Puzzle::check_options(&options).is_err());
+        let mut options = Options::default();
+        options.set("connection", "10");
+        assert!(Puzzle::check_options(&options).is_err());
+    }
+
+    #[test]
+    fn test_part_2_example() {
+        assert_eq!(
+            solve_example::<Puzzle>(EXAMPLE, Part::Two),
+            Answer::Integer(25272)
+        );
+    }
+
+    #[test]
+    fn test_parse_rejects_fewer_than_two_junction_boxes() {
+        assert!(Puzzle::parse("").is_err());
+        assert!(Puzzle::parse("1,2,3\n").is_err());
+        assert!(Puzzle::parse("1,2,3\n4,5,6\n").is_ok());
+    }
+
+    #[test]
+    fn test_parse_rejects_coordinates_that_overflow() {
+        let error = parse_junction_boxes("0,0,0\n5000000000,0,0\n").unwrap_err();
+        assert_eq!((error.line, error.column), (2, 1));
+        assert_eq!(
+            error.message,
+            format!("expected a coordinate of at most {}", MAX_COORDINATE)
+        );
+        let far = format!("2,0,0\n{0},{0},{0}\n", MAX_COORDINATE);
+        let playground = Puzzle::parse(&far).unwrap();
+        assert_eq!(
+            Puzzle::part_2(&playground),
+            Answer::from(2 * MAX_COORDINATE)
+        );
+        assert_eq!(
+            Metric::SquaredEuclidean.distance(&[0; 3], &[MAX_COORDINATE; 3]),
+            3 * MAX_COORDINATE.pow(2)
+        );
+    }
+
+    #[test]
+    fn test_part_2_without_completing_connection() {
+        let playground = Playground {
+            junction_boxes: vec![JunctionBox::new(1, 2, 3)],
+            metric: Metric::SquaredEuclidean,
+            connection_count: None,
+        };
+        assert_eq!(
+            Puzzle::part_2(&playground),
+            Answer::Text("no connection completes the circuit".to_string())
+        );
+    }
+
+    #[test]
+    fn test_minimum_spanning_tree_example() {
+        let junction_boxes = parse_junction_boxes(EXAMPLE).unwrap();
+        let spanning_tree = minimum_spanning_tree(&junction_boxes, Metric::SquaredEuclidean, None);
+        assert_eq!(spanning_tree.connections.len(), junction_boxes.len() - 1);
+        assert_eq!(
+            spanning_tree.connections[0],
+            Connection {
+                from: 0,
+                to: 19,
+                distance: 100427
+            }
+        );
+        assert_eq!(
+            spanning_tree
+                .completing_connection()
+                .map(|c| (c.from, c.to)),
+            Some((10, 12))
+        );
+        let csv = spanning_tree.to_csv(&junction_boxes);
+        assert_eq!(csv.lines().count(), junction_boxes.len());
+        assert_eq!(
+            csv.lines().nth(1),
+            Some("0,19,162,817,812,425,690,689,100427")
+        );
+        let dot = spanning_tree.to_dot(&junction_boxes);
+        assert!(dot.contains("  10 -- 12 [label=\"210094\", color=red, penwidth=2];\n"));
+    }
+
+    #[test]
+    fn test_export_spanning_tree_of_closest_pairs() {
+        let mut options = Options::default();
+        options.set("connections", "10");
+        let csv = export_spanning_tree(EXAMPLE, &options, TreeFormat::Csv).unwrap();
+        // Ten pairs make nine connections, as one pair is already in the same circuit.
+        assert_eq!(csv.lines().count(), 1 + 9);
+        let dot = export_spanning_tree(EXAMPLE, &options, TreeFormat::Dot).unwrap();
+        assert!(!dot.contains("color=red"));
+    }
+}
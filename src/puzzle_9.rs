@@ -1,8 +1,11 @@
+use crate::error::{ParseError, parse_number};
+use crate::solution::{Answer, Solution};
+use crate::utils::text::Text;
 use std::collections::HashSet;
 use std::hash::{Hash, Hasher};
 
 #[derive(Clone, Debug, Eq, PartialEq)]
-struct Point {
+pub struct Point {
     x: usize,
     y: usize,
 }
@@ -17,7 +20,7 @@ impl Point {
     }
 }
 
-impl<'a> Hash for &'a Point {
+impl Hash for &Point {
     fn hash<H: Hasher>(&self, state: &mut H) {
         // Use std::ptr::hash to hash the reference by its address.
         // `*self` is the &Position, and we pass it to the function.
@@ -25,36 +28,40 @@ impl<'a> Hash for &'a Point {
     }
 }
 
-fn parse_points(input: &str) -> Vec<Point> {
-    input
+fn parse_point(line_index: usize, line: &str) -> Result<Point, ParseError> {
+    let (x, y) = line.trim().split_once(',').ok_or_else(|| {
+        ParseError::at(line_index, line, line.trim(), "expected a point like `7,1`")
+    })?;
+    Ok(Point::new(
+        parse_number(line_index, line, x)?,
+        parse_number(line_index, line, y)?,
+    ))
+}
+
+fn parse_points(input: &str) -> Result<Vec<Point>, ParseError> {
+    Text::new(input)
         .lines()
-        .map(|line| {
-            let components = line
-                .split(',')
-                .map(|v| v.parse::<usize>().unwrap())
-                .collect::<Vec<_>>();
-            Point::new(components[0], components[1])
-        })
+        .map(|(line_index, line)| parse_point(line_index, line))
         .collect()
 }
 
 fn align_points_to_0_0(points: Vec<Point>) -> Vec<Point> {
-    let min_x = points.iter().map(|p| p.x).min().unwrap();
-    let min_y = points.iter().map(|p| p.y).min().unwrap();
+    let min_x = points.iter().map(|p| p.x).min().unwrap_or(0);
+    let min_y = points.iter().map(|p| p.y).min().unwrap_or(0);
     points
         .into_iter()
         .map(|p| Point::new(p.x - min_x, p.y - min_y))
         .collect()
 }
 
-fn solve_part_1(points: &Vec<Point>) {
+fn solve_part_1(points: &[Point]) -> usize {
     let mut largest_area = 0;
     for (i, from_point) in points.iter().enumerate() {
         for to_point in points.iter().skip(i + 1) {
             largest_area = largest_area.max(from_point.area(to_point));
         }
     }
-    println!("Part 1: {}", largest_area);
+    largest_area
 }
 
 #[derive(Hash, Eq, PartialEq)]
@@ -88,7 +95,7 @@ impl Rectangle {
     }
 }
 
-fn build_edges(points: &'_ Vec<Point>) -> HashSet<Rectangle> {
+fn build_edges(points: &[Point]) -> HashSet<Rectangle> {
     let mut remaining_points = points.iter().collect::<Vec<_>>();
     let mut edges = HashSet::new();
     while let Some(from_point) = remaining_points.pop() {
@@ -102,7 +109,7 @@ fn build_edges(points: &'_ Vec<Point>) -> HashSet<Rectangle> {
     edges
 }
 
-fn solve_part_2(points: &Vec<Point>) {
+fn solve_part_2(points: &[Point]) -> usize {
     let mut largest_area = 0;
     let edges = build_edges(points);
     for (i, from_point) in points.iter().enumerate() {
@@ -118,12 +125,60 @@ fn solve_part_2(points: &Vec<Point>) {
             largest_area = area;
         }
     }
-    println!("Part 2: {}", largest_area);
+    largest_area
+}
+
+pub struct Puzzle;
+
+impl Solution for Puzzle {
+    const DAY: usize = 9;
+    const TITLE: &'static str = "Movie Theater";
+    const INPUT_PATH: &'static str = "inputs/puzzle_9.txt";
+
+    type Input = Vec<Point>;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        let points = parse_points(input)?;
+        Ok(align_points_to_0_0(points))
+    }
+
+    fn part_1(points: &Self::Input) -> Answer {
+        solve_part_1(points).into()
+    }
+
+    fn part_2(points: &Self::Input) -> Answer {
+        solve_part_2(points).into()
+    }
 }
 
-pub fn solve_puzzle(input: &str) {
-    let points = parse_points(input);
-    let points = align_points_to_0_0(points);
-    solve_part_1(&points);
-    solve_part_2(&points);
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solution::{Part, solve_example};
+    const EXAMPLE: &str = "\
+7,1
+11,1
+11,7
+9,7
+9,5
+2,5
+2,3
+7,3
+";
+
+    #[test]
+    fn test_part_1_example() {
+        assert_eq!(
+            solve_example::<Puzzle>(EXAMPLE, Part::One),
+            Answer::Integer(50)
+        );
+    }
+
+    #[test]
+    fn test_part_2_example() {
+        assert_eq!(
+            solve_example::<Puzzle>(EXAMPLE, Part::Two),
+            Answer::Integer(24)
+        );
+    }
 }
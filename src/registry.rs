@@ -0,0 +1,104 @@
+use crate::solution::{Options, Solution, SolvePuzzle, solve};
+
+/// A solution as known to the runner.
+pub struct Registration {
+    pub day: usize,
+    pub title: &'static str,
+    pub input_path: &'static str,
+    pub solve: SolvePuzzle,
+    pub check_options: fn(&Options) -> Result<(), String>,
+}
+
+impl Registration {
+    pub const fn of<S: Solution>() -> Self {
+        Self {
+            day: S::DAY,
+            title: S::TITLE,
+            input_path: S::INPUT_PATH,
+            solve: solve::<S>,
+            check_options: S::check_options,
+        }
+    }
+}
+
+/// Declares the puzzle modules and registers the `Puzzle` solution of each of them in `PUZZLES`.
+macro_rules! puzzles {
+    ($($module:ident),* $(,)?) => {
+        $(mod $module;)*
+
+        const PUZZLES: &[registry::Registration] =
+            &[$(registry::Registration::of::<$module::Puzzle>()),*];
+    };
+}
+
+pub(crate) use puzzles;
+
+/// The registered solutions, ordered by day.
+pub struct Registry {
+    puzzles: Vec<&'static Registration>,
+}
+
+impl Registry {
+    /// Builds the registry, rejecting days that are registered more than once.
+    pub fn new(registrations: &'static [Registration]) -> Result<Self, String> {
+        let mut puzzles = registrations.iter().collect::<Vec<_>>();
+        puzzles.sort_by_key(|puzzle| puzzle.day);
+        for pair in puzzles.windows(2) {
+            if pair[0].day == pair[1].day {
+                return Err(format!(
+                    "day {} is registered twice, by \"{}\" and \"{}\"",
+                    pair[0].day, pair[0].title, pair[1].title
+                ));
+            }
+        }
+        Ok(Self { puzzles })
+    }
+
+    pub fn get(&self, day: usize) -> Option<&'static Registration> {
+        self.puzzles
+            .binary_search_by_key(&day, |puzzle| puzzle.day)
+            .ok()
+            .map(|index| self.puzzles[index])
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &'static Registration> + '_ {
+        self.puzzles.iter().copied()
+    }
+
+    pub fn days(&self) -> Vec<usize> {
+        self.iter().map(|puzzle| puzzle.day).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{puzzle_1, puzzle_2};
+
+    #[test]
+    fn test_registry_orders_days() {
+        static REGISTRATIONS: [Registration; 2] = [
+            Registration::of::<puzzle_2::Puzzle>(),
+            Registration::of::<puzzle_1::Puzzle>(),
+        ];
+        let registry = Registry::new(&REGISTRATIONS).unwrap();
+        assert_eq!(registry.days(), vec![1, 2]);
+        assert_eq!(registry.get(2).unwrap().title, "Gift Shop");
+        assert!(registry.get(3).is_none());
+    }
+
+    #[test]
+    fn test_registry_rejects_duplicate_days() {
+        static REGISTRATIONS: [Registration; 2] = [
+            Registration::of::<puzzle_1::Puzzle>(),
+            Registration {
+                title: "Copy",
+                ..Registration::of::<puzzle_1::Puzzle>()
+            },
+        ];
+        assert_eq!(
+            Registry::new(&REGISTRATIONS).err().unwrap(),
+            "day 1 is registered twice, by \"Secret Entrance\" and \"Copy\""
+        );
+    }
+}
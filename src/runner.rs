@@ -0,0 +1,339 @@
+use crate::answers::{AnswerStore, Verdict};
+use crate::input::{self, read_puzzle_input};
+use crate::output::{self, Format};
+use crate::solution::{Answer, Options, Part, Report, SolvePuzzle};
+use std::fmt;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
+
+pub struct Verification {
+    pub store: AnswerStore,
+    pub record: bool,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum PartStatus {
+    Checked(Verdict),
+    Recorded,
+    Unchecked,
+}
+
+impl fmt::Display for PartStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PartStatus::Checked(verdict) => write!(f, "{}", verdict),
+            PartStatus::Recorded => f.write_str("RECORDED"),
+            PartStatus::Unchecked => f.write_str("UNCHECKED"),
+        }
+    }
+}
+
+/// The answers of a single day together with the result of checking each of them.
+pub struct DayRun {
+    pub report: Report,
+    pub statuses: Vec<PartStatus>,
+}
+
+impl DayRun {
+    pub fn passed(&self) -> bool {
+        !self
+            .statuses
+            .iter()
+            .any(|status| matches!(status, PartStatus::Checked(Verdict::Fail { .. })))
+    }
+
+    /// Summarises the statuses of both parts, the worst one winning.
+    pub fn status(&self) -> &'static str {
+        let checked = |verdict: fn(&Verdict) -> bool| {
+            self.statuses
+                .iter()
+                .any(|status| matches!(status, PartStatus::Checked(v) if verdict(v)))
+        };
+        if !self.passed() {
+            "FAIL"
+        } else if checked(|verdict| *verdict == Verdict::Unknown)
+            || self.statuses.contains(&PartStatus::Unchecked)
+        {
+            "UNKNOWN"
+        } else if self.statuses.contains(&PartStatus::Recorded) {
+            "RECORDED"
+        } else {
+            "PASS"
+        }
+    }
+}
+
+/// Hashes an input with 64-bit FNV-1a, so that results can be matched with the input they came from.
+pub fn hash_input(input: &str) -> u64 {
+    input.bytes().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+/// Solves a day and checks its answers, recording unknown ones when asked to.
+pub fn run_day(
+    day: usize,
+    solve_puzzle: SolvePuzzle,
+    path: &str,
+    puzzle_input: &str,
+    parts: &[Part],
+    options: &Options,
+    verification: Option<&Verification>,
+) -> Result<DayRun, String> {
+    let report = solve_puzzle(puzzle_input, parts, options).map_err(|error| {
+        error
+            .with_day(day)
+            .render(input::display_name(path), puzzle_input)
+    })?;
+    let Some(verification) = verification else {
+        let statuses = report.parts.iter().map(|_| PartStatus::Unchecked).collect();
+        return Ok(DayRun { report, statuses });
+    };
+    let mut expected_answers = verification.store.load(day)?;
+    let mut recorded = false;
+    let mut statuses = Vec::new();
+    for part in report.parts.iter() {
+        let verdict = expected_answers.verify(part.part, &part.answer);
+        if verdict == Verdict::Unknown
+            && verification.record
+            && part.answer != Answer::NotImplemented
+        {
+            expected_answers.set(part.part, part.answer.to_string());
+            recorded = true;
+            statuses.push(PartStatus::Recorded);
+        } else {
+            statuses.push(PartStatus::Checked(verdict));
+        }
+    }
+    if recorded {
+        verification.store.save(day, &expected_answers)?;
+    }
+    Ok(DayRun { report, statuses })
+}
+
+pub struct DayOutcome {
+    pub day: usize,
+    pub path: String,
+    /// The hash of the input, missing when it could not be read.
+    pub input_hash: Option<u64>,
+    pub result: Result<DayRun, String>,
+}
+
+fn panic_message(day: usize, payload: &(dyn std::any::Any + Send)) -> String {
+    let message = payload
+        .downcast_ref::<&str>()
+        .copied()
+        .or_else(|| payload.downcast_ref::<String>().map(String::as_str))
+        .unwrap_or("unknown cause");
+    format!("day {} panicked: {}", day, message)
+}
+
+fn run_day_outcome(
+    day: usize,
+    solve_puzzle: SolvePuzzle,
+    path: &str,
+    parts: &[Part],
+    options: &Options,
+    verification: Option<&Verification>,
+) -> DayOutcome {
+    let puzzle_input = read_puzzle_input(path);
+    let input_hash = puzzle_input.as_deref().ok().map(hash_input);
+    let result = puzzle_input.and_then(|puzzle_input| {
+        panic::catch_unwind(AssertUnwindSafe(|| {
+            run_day(
+                day,
+                solve_puzzle,
+                path,
+                &puzzle_input,
+                parts,
+                options,
+                verification,
+            )
+        }))
+        .unwrap_or_else(|payload| Err(panic_message(day, payload.as_ref())))
+    });
+    DayOutcome {
+        day,
+        path: path.to_string(),
+        input_hash,
+        result,
+    }
+}
+
+/// Runs the given days on up to `jobs` threads, carrying on when a day fails or panics.
+///
+/// The outcomes are returned in the order of `days`.
+pub fn run_days(
+    days: &[(usize, SolvePuzzle, &str)],
+    parts: &[Part],
+    options: &Options,
+    verification: Option<&Verification>,
+    jobs: usize,
+) -> Vec<DayOutcome> {
+    let next = AtomicUsize::new(0);
+    let outcomes = Mutex::new(Vec::new());
+    std::thread::scope(|scope| {
+        for _ in 0..jobs.clamp(1, days.len().max(1)) {
+            scope.spawn(|| {
+                while let Some(&(day, solve_puzzle, path)) =
+                    days.get(next.fetch_add(1, Ordering::Relaxed))
+                {
+                    let outcome =
+                        run_day_outcome(day, solve_puzzle, path, parts, options, verification);
+                    outcomes.lock().unwrap().push(outcome);
+                }
+            });
+        }
+    });
+    let mut outcomes = outcomes.into_inner().unwrap();
+    outcomes.sort_by_key(|outcome| days.iter().position(|&(day, ..)| day == outcome.day));
+    outcomes
+}
+
+fn answer_of(run: &DayRun, part: Part) -> String {
+    run.report
+        .parts
+        .iter()
+        .find(|report| report.part == part)
+        .map_or_else(|| "-".to_string(), |report| report.answer.to_string())
+}
+
+/// Prints one row per day followed by the total elapsed time, then the errors of failed days.
+pub fn print_summary(outcomes: &[DayOutcome], elapsed: Duration) {
+    let mut rows = vec![[
+        "Day".to_string(),
+        "Part 1".to_string(),
+        "Part 2".to_string(),
+        "Time".to_string(),
+        "Status".to_string(),
+    ]];
+    for outcome in outcomes {
+        rows.push(match &outcome.result {
+            Ok(run) => [
+                outcome.day.to_string(),
+                answer_of(run, Part::One),
+                answer_of(run, Part::Two),
+                format!("{:.2?}", run.report.total_elapsed()),
+                run.status().to_string(),
+            ],
+            Err(_) => [
+                outcome.day.to_string(),
+                "-".to_string(),
+                "-".to_string(),
+                "-".to_string(),
+                "ERROR".to_string(),
+            ],
+        });
+    }
+    let mut widths = [0; 5];
+    for row in rows.iter() {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+    for (index, row) in rows.iter().enumerate() {
+        println!(
+            "{:>w0$}  {:>w1$}  {:>w2$}  {:>w3$}  {}",
+            row[0],
+            row[1],
+            row[2],
+            row[3],
+            row[4],
+            w0 = widths[0],
+            w1 = widths[1],
+            w2 = widths[2],
+            w3 = widths[3],
+        );
+        if index == 0 {
+            println!("{}", "-".repeat(widths.iter().sum::<usize>() + 2 * 4));
+        }
+    }
+    println!("Total elapsed: {:.2?}", elapsed);
+    for outcome in outcomes {
+        if let Err(error) = &outcome.result {
+            eprintln!("\nerror: {}", error);
+        }
+    }
+}
+
+/// Runs the given days and prints the results in `format`, returning whether every day ran and
+/// passed.
+pub fn run_all(
+    days: &[(usize, SolvePuzzle, &str)],
+    parts: &[Part],
+    options: &Options,
+    verification: Option<&Verification>,
+    jobs: usize,
+    format: Format,
+) -> bool {
+    let start_time = Instant::now();
+    let outcomes = run_days(days, parts, options, verification, jobs);
+    match format {
+        Format::Text => print_summary(&outcomes, start_time.elapsed()),
+        Format::Json => print!("{}", output::to_json(&outcomes)),
+        Format::Csv => print!("{}", output::to_csv(&outcomes)),
+    }
+    outcomes
+        .iter()
+        .all(|outcome| outcome.result.as_ref().is_ok_and(DayRun::passed))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::ParseError;
+    use crate::solution::PartReport;
+
+    fn solve_ok(_input: &str, parts: &[Part], _options: &Options) -> Result<Report, ParseError> {
+        Ok(Report {
+            parse_elapsed: Duration::ZERO,
+            parts: parts
+                .iter()
+                .map(|&part| PartReport {
+                    part,
+                    answer: Answer::Integer(1),
+                    elapsed: Duration::ZERO,
+                })
+                .collect(),
+        })
+    }
+
+    fn solve_panics(
+        _input: &str,
+        _parts: &[Part],
+        _options: &Options,
+    ) -> Result<Report, ParseError> {
+        panic!("no solver available")
+    }
+
+    #[test]
+    fn test_hash_input() {
+        assert_eq!(hash_input(""), 0xcbf29ce484222325);
+        assert_eq!(hash_input("a"), 0xaf63dc4c8601ec8c);
+    }
+
+    #[test]
+    fn test_run_days_keeps_going_after_a_failure() {
+        let path = "Cargo.toml";
+        let days: [(usize, SolvePuzzle, &str); 4] = [
+            (1, solve_ok, path),
+            (2, solve_panics, path),
+            (3, solve_ok, "missing.txt"),
+            (4, solve_ok, path),
+        ];
+        let outcomes = run_days(&days, &[Part::One], &Options::default(), None, 3);
+        let results = outcomes
+            .iter()
+            .map(|outcome| (outcome.day, outcome.result.as_ref().err().cloned()))
+            .collect::<Vec<_>>();
+        assert_eq!(results[0], (1, None));
+        assert_eq!(
+            results[1],
+            (2, Some("day 2 panicked: no solver available".to_string()))
+        );
+        assert!(results[2].1.as_ref().unwrap().contains("missing.txt"));
+        assert_eq!(results[3], (4, None));
+    }
+}
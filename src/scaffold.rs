@@ -0,0 +1,175 @@
+use std::path::{Path, PathBuf};
+
+const REGISTRY_START: &str = "registry::puzzles! {";
+
+const MODULE_TEMPLATE: &str = r#"use crate::error::ParseError;
+use crate::solution::{Answer, Solution};
+use crate::utils::text::Text;
+
+pub struct Puzzle;
+
+impl Solution for Puzzle {
+    const DAY: usize = {day};
+    const TITLE: &'static str = "{title}";
+    const INPUT_PATH: &'static str = "inputs/puzzle_{day}.txt";
+
+    type Input = Vec<String>;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        Ok(Text::new(input)
+            .lines()
+            .map(|(_, line)| line.to_string())
+            .collect())
+    }
+
+    fn part_1(_input: &Self::Input) -> Answer {
+        Answer::NotImplemented
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solution::{Part, solve_example};
+
+    const EXAMPLE: &str = "\
+";
+
+    #[test]
+    fn test_part_1_example() {
+        assert_eq!(
+            solve_example::<Puzzle>(EXAMPLE, Part::One),
+            Answer::NotImplemented
+        );
+    }
+}
+"#;
+
+fn module_source(day: usize, title: &str) -> String {
+    let title = title.replace('\\', "\\\\").replace('"', "\\\"");
+    MODULE_TEMPLATE
+        .replace("{day}", &day.to_string())
+        .replace("{title}", &title)
+}
+
+fn module_day(line: &str) -> Option<usize> {
+    line.trim()
+        .strip_prefix("puzzle_")?
+        .strip_suffix(',')?
+        .parse()
+        .ok()
+}
+
+/// Adds `puzzle_<day>` to the `registry::puzzles!` invocation in `main_source`, keeping the
+/// modules ordered by day.
+fn register_module(main_source: &str, day: usize) -> Result<String, String> {
+    let lines = main_source.lines().collect::<Vec<_>>();
+    let start = lines
+        .iter()
+        .position(|line| line.trim() == REGISTRY_START)
+        .ok_or_else(|| format!("could not find `{}`", REGISTRY_START))?;
+    let end = start
+        + lines[start..]
+            .iter()
+            .position(|line| line.trim() == "}")
+            .ok_or_else(|| format!("could not find the end of `{}`", REGISTRY_START))?;
+    let registered_days = lines[start + 1..end]
+        .iter()
+        .filter_map(|line| module_day(line))
+        .collect::<Vec<_>>();
+    if registered_days.contains(&day) {
+        return Err(format!("day {} is already registered", day));
+    }
+    let insert_at = start
+        + 1
+        + lines[start + 1..end]
+            .iter()
+            .take_while(|line| module_day(line).is_none_or(|registered| registered < day))
+            .count();
+    let module_line = format!("    puzzle_{},", day);
+    let mut result = lines[..insert_at].join("\n");
+    result.push('\n');
+    result.push_str(&module_line);
+    result.push('\n');
+    result.push_str(&lines[insert_at..].join("\n"));
+    result.push('\n');
+    Ok(result)
+}
+
+fn write_file(path: &Path, contents: &str) -> Result<(), String> {
+    path.parent()
+        .map_or(Ok(()), std::fs::create_dir_all)
+        .and_then(|_| std::fs::write(path, contents))
+        .map_err(|error| format!("failed to write `{}`: {}", path.display(), error))
+}
+
+/// Creates the module and an empty input for a new day below `root` and registers the module,
+/// returning the paths that were written.
+pub fn new_day(root: &Path, day: usize, title: &str) -> Result<Vec<PathBuf>, String> {
+    let module_path = root.join(format!("src/puzzle_{}.rs", day));
+    let input_path = root.join(format!("inputs/puzzle_{}.txt", day));
+    let main_path = root.join("src/main.rs");
+    for path in [&module_path, &input_path] {
+        if path.exists() {
+            return Err(format!(
+                "`{}` already exists, refusing to overwrite it",
+                path.display()
+            ));
+        }
+    }
+    let main_source = std::fs::read_to_string(&main_path)
+        .map_err(|error| format!("failed to read `{}`: {}", main_path.display(), error))?;
+    let main_source = register_module(&main_source, day)?;
+    write_file(&module_path, &module_source(day, title))?;
+    write_file(&input_path, "")?;
+    write_file(&main_path, &main_source)?;
+    Ok(vec![module_path, input_path, main_path])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MAIN_SOURCE: &str = "\
+mod registry;
+
+registry::puzzles! {
+    puzzle_1,
+    puzzle_2,
+    puzzle_10,
+}
+
+fn main() {}
+";
+
+    #[test]
+    fn test_register_module() {
+        assert_eq!(
+            register_module(MAIN_SOURCE, 3),
+            Ok(MAIN_SOURCE.replace("    puzzle_10,", "    puzzle_3,\n    puzzle_10,"))
+        );
+        assert_eq!(
+            register_module(MAIN_SOURCE, 11),
+            Ok(MAIN_SOURCE.replace("    puzzle_10,", "    puzzle_10,\n    puzzle_11,"))
+        );
+        assert!(register_module(MAIN_SOURCE, 2).is_err());
+    }
+
+    #[test]
+    fn test_new_day_refuses_to_overwrite() {
+        let root = std::env::temp_dir().join(format!("scaffold-{}", std::process::id()));
+        write_file(&root.join("src/main.rs"), MAIN_SOURCE).unwrap();
+        assert!(new_day(&root, 3, "Lobby").is_ok());
+        assert!(
+            std::fs::read_to_string(root.join("src/puzzle_3.rs"))
+                .unwrap()
+                .contains("const TITLE: &'static str = \"Lobby\";")
+        );
+        assert_eq!(
+            std::fs::read_to_string(root.join("inputs/puzzle_3.txt")).unwrap(),
+            ""
+        );
+        assert!(new_day(&root, 3, "Lobby").is_err());
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+}
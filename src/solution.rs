@@ -0,0 +1,199 @@
+use crate::error::ParseError;
+use std::fmt;
+use std::str::FromStr;
+use std::time::{Duration, Instant};
+
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum Part {
+    One,
+    Two,
+}
+
+impl fmt::Display for Part {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Part::One => f.write_str("1"),
+            Part::Two => f.write_str("2"),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Answer {
+    Integer(i64),
+    Text(String),
+    NotImplemented,
+}
+
+impl fmt::Display for Answer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Answer::Integer(value) => write!(f, "{}", value),
+            Answer::Text(value) => f.write_str(value),
+            Answer::NotImplemented => f.write_str("not implemented"),
+        }
+    }
+}
+
+impl From<i64> for Answer {
+    fn from(value: i64) -> Self {
+        Answer::Integer(value)
+    }
+}
+
+impl From<usize> for Answer {
+    fn from(value: usize) -> Self {
+        Answer::Integer(i64::try_from(value).expect("answer does not fit in an i64"))
+    }
+}
+
+impl From<u64> for Answer {
+    fn from(value: u64) -> Self {
+        Answer::Integer(i64::try_from(value).expect("answer does not fit in an i64"))
+    }
+}
+
+impl From<String> for Answer {
+    fn from(value: String) -> Self {
+        Answer::Text(value)
+    }
+}
+
+/// Day-specific settings, given on the command line as `--set key=value`.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Options {
+    values: Vec<(String, String)>,
+}
+
+impl Options {
+    /// Sets `key` to `value`, replacing any earlier value.
+    pub fn set(&mut self, key: &str, value: &str) {
+        match self.values.iter_mut().find(|(existing, _)| existing == key) {
+            Some((_, existing)) => *existing = value.to_string(),
+            None => self.values.push((key.to_string(), value.to_string())),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    /// Rejects any key that is not one of `known`.
+    pub fn check_keys(&self, known: &[&str]) -> Result<(), String> {
+        match self
+            .values
+            .iter()
+            .find(|(key, _)| !known.contains(&key.as_str()))
+        {
+            Some((key, _)) if known.is_empty() => {
+                Err(format!("unknown setting `{}`, this day has none", key))
+            }
+            Some((key, _)) => Err(format!(
+                "unknown setting `{}`, expected one of: {}",
+                key,
+                known.join(", ")
+            )),
+            None => Ok(()),
+        }
+    }
+
+    pub fn get<T: FromStr>(&self, key: &str) -> Result<Option<T>, String>
+    where
+        T::Err: fmt::Display,
+    {
+        let Some((_, value)) = self.values.iter().find(|(existing, _)| existing == key) else {
+            return Ok(None);
+        };
+        value
+            .parse()
+            .map(Some)
+            .map_err(|error| format!("invalid value `{}` for setting `{}`: {}", value, key, error))
+    }
+}
+
+/// A puzzle solver. The input is parsed once and then shared by both parts.
+pub trait Solution {
+    const DAY: usize;
+    const TITLE: &'static str;
+    /// The input used when none is given on the command line.
+    const INPUT_PATH: &'static str;
+
+    type Input;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError>;
+
+    /// Checks the settings given on the command line, before any input is read.
+    fn check_options(options: &Options) -> Result<(), String> {
+        options.check_keys(&[])
+    }
+
+    /// Parses the input with settings that passed [`Solution::check_options`].
+    fn parse_with_options(input: &str, _options: &Options) -> Result<Self::Input, ParseError> {
+        Self::parse(input)
+    }
+
+    fn part_1(input: &Self::Input) -> Answer;
+
+    fn part_2(_input: &Self::Input) -> Answer {
+        Answer::NotImplemented
+    }
+}
+
+pub struct PartReport {
+    pub part: Part,
+    pub answer: Answer,
+    pub elapsed: Duration,
+}
+
+/// The answers of a single run, with the time spent parsing and solving each part.
+pub struct Report {
+    pub parse_elapsed: Duration,
+    pub parts: Vec<PartReport>,
+}
+
+impl Report {
+    pub fn total_elapsed(&self) -> Duration {
+        self.parse_elapsed + self.parts.iter().map(|part| part.elapsed).sum::<Duration>()
+    }
+}
+
+pub type SolvePuzzle = fn(&str, &[Part], &Options) -> Result<Report, ParseError>;
+
+pub fn solve<S: Solution>(
+    input: &str,
+    parts: &[Part],
+    options: &Options,
+) -> Result<Report, ParseError> {
+    let start_time = Instant::now();
+    let input = S::parse_with_options(input, options)?;
+    let parse_elapsed = start_time.elapsed();
+    let parts = parts
+        .iter()
+        .map(|&part| {
+            let start_time = Instant::now();
+            let answer = match part {
+                Part::One => S::part_1(&input),
+                Part::Two => S::part_2(&input),
+            };
+            PartReport {
+                part,
+                answer,
+                elapsed: start_time.elapsed(),
+            }
+        })
+        .collect();
+    Ok(Report {
+        parse_elapsed,
+        parts,
+    })
+}
+
+/// Parses a worked example and solves a single part of it.
+#[cfg(test)]
+pub fn solve_example<S: Solution>(input: &str, part: Part) -> Answer {
+    let input = S::parse(input).expect("example input should parse");
+    match part {
+        Part::One => S::part_1(&input),
+        Part::Two => S::part_2(&input),
+    }
+}
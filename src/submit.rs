@@ -0,0 +1,245 @@
+use crate::answers::{AnswerStore, Rejection};
+use crate::fetch::Client;
+use crate::solution::{Answer, Part};
+use std::fmt;
+use std::time::Duration;
+
+/// The puzzle server's verdict on a submitted answer.
+#[derive(Debug, PartialEq)]
+pub enum Outcome {
+    Correct,
+    Rejected(Rejection),
+    RateLimited { wait: Duration },
+    AlreadySolved,
+}
+
+impl fmt::Display for Outcome {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Outcome::Correct => f.write_str("correct"),
+            Outcome::Rejected(Rejection::TooHigh) => f.write_str("wrong, too high"),
+            Outcome::Rejected(Rejection::TooLow) => f.write_str("wrong, too low"),
+            Outcome::Rejected(Rejection::Wrong) => f.write_str("wrong"),
+            Outcome::RateLimited { wait } => {
+                let seconds = wait.as_secs();
+                write!(
+                    f,
+                    "rate limited, try again in {}m {}s",
+                    seconds / 60,
+                    seconds % 60
+                )
+            }
+            Outcome::AlreadySolved => f.write_str("already solved"),
+        }
+    }
+}
+
+/// Reduces the HTML of a reply to the text of its `<article>`.
+fn article_text(html: &str) -> String {
+    let article = html
+        .split_once("<article")
+        .and_then(|(_, rest)| rest.split_once('>'))
+        .and_then(|(_, rest)| rest.split_once("</article>"))
+        .map_or(html, |(article, _)| article);
+    let mut text = String::new();
+    let mut in_tag = false;
+    for c in article.chars() {
+        match c {
+            '<' => in_tag = true,
+            '>' => in_tag = false,
+            c if !in_tag => text.push(c),
+            _ => {}
+        }
+    }
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Parses the wait in a message like "You have 1m 5s left to wait."
+fn parse_wait(text: &str) -> Option<Duration> {
+    let (_, rest) = text.split_once("You have ")?;
+    let (wait, _) = rest.split_once(" left to wait")?;
+    wait.split_whitespace()
+        .map(|amount| {
+            let (number, unit) = amount.split_at(amount.len().checked_sub(1)?);
+            let number = number.parse::<u64>().ok()?;
+            match unit {
+                "h" => Some(number * 3600),
+                "m" => Some(number * 60),
+                "s" => Some(number),
+                _ => None,
+            }
+        })
+        .sum::<Option<u64>>()
+        .map(Duration::from_secs)
+}
+
+pub fn parse_reply(html: &str) -> Result<Outcome, String> {
+    let text = article_text(html);
+    if text.contains("That's the right answer") {
+        Ok(Outcome::Correct)
+    } else if text.contains("That's not the right answer") {
+        Ok(Outcome::Rejected(
+            if text.contains("your answer is too high") {
+                Rejection::TooHigh
+            } else if text.contains("your answer is too low") {
+                Rejection::TooLow
+            } else {
+                Rejection::Wrong
+            },
+        ))
+    } else if text.contains("You gave an answer too recently") {
+        let wait = parse_wait(&text)
+            .ok_or_else(|| format!("could not find the wait time in the reply: {}", text))?;
+        Ok(Outcome::RateLimited { wait })
+    } else if text.contains("Did you already complete it?") {
+        Ok(Outcome::AlreadySolved)
+    } else {
+        Err(format!("unexpected reply from the puzzle server: {}", text))
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum Submission {
+    /// The answer matches the recorded correct answer, so there is nothing to submit.
+    AlreadyKnown,
+    /// The answer differs from the recorded correct answer.
+    Differs {
+        expected: String,
+    },
+    /// The answer was rejected before, or is outside the known bounds.
+    KnownWrong {
+        reason: String,
+    },
+    Sent(Outcome),
+}
+
+/// Submits `answer` unless the answers store already settles it, recording what the server says.
+pub fn submit_answer(
+    client: &mut Client,
+    store: &AnswerStore,
+    day: usize,
+    part: Part,
+    answer: &Answer,
+) -> Result<Submission, String> {
+    let mut answers = store.load(day)?;
+    if let Some(expected) = answers.get(part) {
+        return Ok(if expected == answer.to_string() {
+            Submission::AlreadyKnown
+        } else {
+            Submission::Differs {
+                expected: expected.to_string(),
+            }
+        });
+    }
+    if let Some(reason) = answers.known_wrong(part, answer) {
+        return Ok(Submission::KnownWrong { reason });
+    }
+    let reply = client.post_form(
+        &format!("day/{}/answer", day),
+        &[
+            ("level", &part.to_string()),
+            ("answer", &answer.to_string()),
+        ],
+    )?;
+    let outcome = parse_reply(&reply)?;
+    match outcome {
+        Outcome::Correct => answers.set(part, answer.to_string()),
+        Outcome::Rejected(rejection) => answers.reject(part, answer, rejection),
+        Outcome::RateLimited { .. } | Outcome::AlreadySolved => {
+            return Ok(Submission::Sent(outcome));
+        }
+    }
+    store.save(day, &answers)?;
+    Ok(Submission::Sent(outcome))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+    use crate::test_server::TestServer;
+
+    fn reply(message: &str) -> String {
+        format!(
+            "<!DOCTYPE html>\n<html><body><main>\n<article><p>{}</p></article>\n</main></body></html>",
+            message
+        )
+    }
+
+    #[test]
+    fn test_parse_reply() {
+        assert_eq!(
+            parse_reply(&reply(
+                "That's the right answer!  You are <span class=\"day-success\">one gold star</span> closer."
+            )),
+            Ok(Outcome::Correct)
+        );
+        assert_eq!(
+            parse_reply(&reply(
+                "That's not the right answer; your answer is too high.  If you're stuck, make sure \
+                 you're using the full input data. Please wait one minute before trying again."
+            )),
+            Ok(Outcome::Rejected(Rejection::TooHigh))
+        );
+        assert_eq!(
+            parse_reply(&reply(
+                "That's not the right answer.  If you're stuck, make sure you're using the full \
+                 input data."
+            )),
+            Ok(Outcome::Rejected(Rejection::Wrong))
+        );
+        assert_eq!(
+            parse_reply(&reply(
+                "You gave an answer too recently; you have to wait after submitting an answer \
+                 before trying again.  You have 4m 25s left to wait."
+            )),
+            Ok(Outcome::RateLimited {
+                wait: Duration::from_secs(265)
+            })
+        );
+        assert_eq!(
+            parse_reply(&reply(
+                "You don't seem to be solving the right level.  Did you already complete it?"
+            )),
+            Ok(Outcome::AlreadySolved)
+        );
+        assert!(parse_reply(&reply("Something else")).is_err());
+    }
+
+    #[test]
+    fn test_submit_answer_never_resubmits_wrong_answers() {
+        let server = TestServer::start(|request| {
+            let message = if request.body.contains("answer=42") {
+                "That's the right answer!"
+            } else {
+                "That's not the right answer; your answer is too low."
+            };
+            (200, reply(message))
+        });
+        let mut client = Client::new(&Config {
+            session: Some("abc".to_string()),
+            base_url: server.url.clone(),
+            min_delay: Duration::ZERO,
+            contact: None,
+        });
+        let dir = std::env::temp_dir().join(format!("submit-{}", std::process::id()));
+        let store = AnswerStore::new(&dir);
+        let mut submit =
+            |answer| submit_answer(&mut client, &store, 5, Part::Two, &Answer::Integer(answer));
+        assert_eq!(
+            submit(7),
+            Ok(Submission::Sent(Outcome::Rejected(Rejection::TooLow)))
+        );
+        assert!(matches!(submit(7), Ok(Submission::KnownWrong { .. })));
+        assert!(matches!(submit(3), Ok(Submission::KnownWrong { .. })));
+        assert_eq!(submit(42), Ok(Submission::Sent(Outcome::Correct)));
+        assert_eq!(submit(42), Ok(Submission::AlreadyKnown));
+        let requests = server.requests();
+        assert_eq!(requests.len(), 2);
+        assert_eq!(requests[0].method, "POST");
+        assert_eq!(requests[0].path, "/2025/day/5/answer");
+        assert_eq!(requests[0].body, "level=2&answer=7");
+        assert_eq!(store.load(5).unwrap().get(Part::Two), Some("42"));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -1,17 +1,110 @@
-pub fn extract_lines(input: &str) -> Vec<&str> {
-    input.lines().collect()
+use std::fmt;
+
+pub mod disjoint_set;
+pub mod grid;
+pub mod kd_tree;
+pub mod metric;
+#[cfg(test)]
+pub mod random;
+pub mod text;
+
+/// A row whose length differs from the length of the first row.
+#[derive(Debug, PartialEq)]
+pub struct RaggedRow {
+    pub row: usize,
+    pub len: usize,
+    pub expected: usize,
+}
+
+impl fmt::Display for RaggedRow {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "row {} has {} items, expected {}",
+            self.row, self.len, self.expected
+        )
+    }
+}
+
+impl std::error::Error for RaggedRow {}
+
+/// Returns the common length of `rows`, which is 0 when there are none.
+fn row_len<T>(rows: &[Vec<T>]) -> Result<usize, RaggedRow> {
+    let expected = rows.first().map_or(0, Vec::len);
+    match rows.iter().position(|row| row.len() != expected) {
+        Some(row) => Err(RaggedRow {
+            row,
+            len: rows[row].len(),
+            expected,
+        }),
+        None => Ok(expected),
+    }
+}
+
+/// Iterates over the columns of `rows` without building the transposed rows.
+pub fn columns<T>(
+    rows: &[Vec<T>],
+) -> Result<impl Iterator<Item = impl Iterator<Item = &T>>, RaggedRow> {
+    let len = row_len(rows)?;
+    Ok((0..len).map(move |x| rows.iter().map(move |row| &row[x])))
+}
+
+pub fn transpose<T: Clone>(rows: &[Vec<T>]) -> Result<Vec<Vec<T>>, RaggedRow> {
+    Ok(columns(rows)?
+        .map(|column| column.cloned().collect())
+        .collect())
 }
 
-pub fn transpose<T>(v: Vec<Vec<T>>) -> Vec<Vec<T>> {
-    assert!(!v.is_empty());
-    let len = v[0].len();
-    let mut iters: Vec<_> = v.into_iter().map(|n| n.into_iter()).collect();
+/// Transposes `rows` as if every row was padded with `fill` to the length of the longest one.
+pub fn transpose_padded<T: Clone>(rows: &[Vec<T>], fill: T) -> Vec<Vec<T>> {
+    let len = rows.iter().map(Vec::len).max().unwrap_or(0);
     (0..len)
-        .map(|_| {
-            iters
-                .iter_mut()
-                .map(|n| n.next().unwrap())
-                .collect::<Vec<T>>()
+        .map(|x| {
+            rows.iter()
+                .map(|row| row.get(x).unwrap_or(&fill).clone())
+                .collect()
         })
         .collect()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_transpose() {
+        let rows = vec![vec![1, 2, 3], vec![4, 5, 6]];
+        assert_eq!(
+            transpose(&rows),
+            Ok(vec![vec![1, 4], vec![2, 5], vec![3, 6]])
+        );
+        assert_eq!(transpose::<i32>(&[]), Ok(vec![]));
+        assert_eq!(
+            transpose(&[vec![1, 2], vec![3, 4], vec![5]]),
+            Err(RaggedRow {
+                row: 2,
+                len: 1,
+                expected: 2
+            })
+        );
+    }
+
+    #[test]
+    fn test_transpose_padded() {
+        let rows = vec![vec!['1', '2'], vec!['3'], vec![]];
+        assert_eq!(
+            transpose_padded(&rows, ' '),
+            vec![vec!['1', '3', ' '], vec!['2', ' ', ' ']]
+        );
+    }
+
+    #[test]
+    fn test_columns() {
+        let rows = vec![vec![1, 2], vec![3, 4]];
+        let mut columns = columns(&rows).unwrap();
+        assert_eq!(columns.next().unwrap().sum::<i32>(), 4);
+        assert_eq!(columns.next().unwrap().collect::<Vec<_>>(), [&2, &4]);
+        assert!(columns.next().is_none());
+        assert!(super::columns(&[vec![1], vec![]]).is_err());
+    }
+}
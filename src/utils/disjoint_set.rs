@@ -0,0 +1,91 @@
+/// A partition of `0..len` into disjoint sets, with union by size and path compression.
+#[derive(Clone, Debug)]
+pub struct DisjointSet {
+    parents: Vec<usize>,
+    /// The size of each set, only up to date at its root.
+    sizes: Vec<usize>,
+    component_count: usize,
+}
+
+impl DisjointSet {
+    /// Creates `len` sets of one element each.
+    pub fn new(len: usize) -> Self {
+        Self {
+            parents: (0..len).collect(),
+            sizes: vec![1; len],
+            component_count: len,
+        }
+    }
+
+    /// Returns the root of the set containing `element`.
+    pub fn find(&mut self, element: usize) -> usize {
+        let mut root = element;
+        while self.parents[root] != root {
+            root = self.parents[root];
+        }
+        let mut element = element;
+        while self.parents[element] != root {
+            element = std::mem::replace(&mut self.parents[element], root);
+        }
+        root
+    }
+
+    /// Merges the sets containing `a` and `b`, returning whether they were separate.
+    pub fn union(&mut self, a: usize, b: usize) -> bool {
+        let (a, b) = (self.find(a), self.find(b));
+        if a == b {
+            return false;
+        }
+        let (larger, smaller) = if self.sizes[a] >= self.sizes[b] {
+            (a, b)
+        } else {
+            (b, a)
+        };
+        self.parents[smaller] = larger;
+        self.sizes[larger] += self.sizes[smaller];
+        self.component_count -= 1;
+        true
+    }
+
+    pub fn component_count(&self) -> usize {
+        self.component_count
+    }
+
+    /// The size of every set, in the order of their roots.
+    pub fn component_sizes(&self) -> Vec<usize> {
+        (0..self.parents.len())
+            .filter(|&element| self.parents[element] == element)
+            .map(|root| self.sizes[root])
+            .collect()
+    }
+
+    /// The elements in the same set as `element`, in increasing order.
+    #[cfg_attr(not(test), allow(dead_code))]
+    pub fn members(&mut self, element: usize) -> Vec<usize> {
+        let root = self.find(element);
+        (0..self.parents.len())
+            .filter(|&other| self.find(other) == root)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_disjoint_set() {
+        let mut set = DisjointSet::new(6);
+        assert!(set.union(0, 1));
+        assert!(set.union(2, 3));
+        assert!(set.union(1, 3));
+        assert!(!set.union(0, 2));
+        assert_eq!(set.find(0), set.find(3));
+        assert_ne!(set.find(0), set.find(4));
+        assert_eq!(set.component_count(), 3);
+        assert_eq!(set.members(3), [0, 1, 2, 3]);
+        let mut sizes = set.component_sizes();
+        sizes.sort();
+        assert_eq!(sizes, [1, 1, 4]);
+    }
+}
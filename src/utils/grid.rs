@@ -0,0 +1,261 @@
+use crate::error::ParseError;
+use crate::utils::text::NumberedLine;
+use std::fmt;
+use std::ops::{Index, IndexMut};
+
+/// A cell position as `(x, y)`, with `y` growing downwards.
+pub type Position = (usize, usize);
+
+#[cfg_attr(not(test), allow(dead_code))]
+const NEIGHBOUR_OFFSETS_4: [(isize, isize); 4] = [(0, -1), (1, 0), (0, 1), (-1, 0)];
+const NEIGHBOUR_OFFSETS_8: [(isize, isize); 8] = [
+    (-1, -1),
+    (0, -1),
+    (1, -1),
+    (1, 0),
+    (1, 1),
+    (0, 1),
+    (-1, 1),
+    (-1, 0),
+];
+
+/// A rectangular grid stored row by row in a single `Vec`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+impl<T> Grid<T> {
+    pub fn new(width: usize, height: usize, value: T) -> Self
+    where
+        T: Clone,
+    {
+        Self {
+            width,
+            height,
+            cells: vec![value; width * height],
+        }
+    }
+
+    /// Parses every character of `lines` with `parse_cell`, which returns `None` for a character
+    /// that is not one of the `expected` ones. All rows must be as long as the first.
+    pub fn parse(
+        lines: &[NumberedLine],
+        expected: &str,
+        parse_cell: impl Fn(char) -> Option<T>,
+    ) -> Result<Self, ParseError> {
+        let mut cells = Vec::new();
+        let mut width = None;
+        for &(line_index, line) in lines {
+            let row_start = cells.len();
+            for (i, c) in line.char_indices() {
+                cells.push(parse_cell(c).ok_or_else(|| {
+                    ParseError::at(
+                        line_index,
+                        line,
+                        &line[i..i + c.len_utf8()],
+                        format!("expected {}", expected),
+                    )
+                })?);
+            }
+            let row_width = cells.len() - row_start;
+            match width {
+                Some(width) if width != row_width => {
+                    return Err(ParseError::at(
+                        line_index,
+                        line,
+                        line,
+                        format!("expected a row of {} cells", width),
+                    ));
+                }
+                _ => width = Some(row_width),
+            }
+        }
+        Ok(Self {
+            width: width.unwrap_or(0),
+            height: lines.len(),
+            cells,
+        })
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    fn contains(&self, (x, y): Position) -> bool {
+        x < self.width && y < self.height
+    }
+
+    pub fn get(&self, position: Position) -> Option<&T> {
+        self.contains(position).then(|| &self[position])
+    }
+
+    pub fn row(&self, y: usize) -> &[T] {
+        &self.cells[y * self.width..(y + 1) * self.width]
+    }
+
+    #[cfg_attr(not(test), allow(dead_code))]
+    pub fn column(&self, x: usize) -> impl Iterator<Item = &T> {
+        assert!(x < self.width, "column {} is outside the grid", x);
+        (0..self.height).map(move |y| &self[(x, y)])
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        (0..self.height).map(|y| self.row(y))
+    }
+
+    /// All positions, row by row.
+    pub fn positions(&self) -> impl Iterator<Item = Position> + use<T> {
+        let width = self.width;
+        (0..self.height).flat_map(move |y| (0..width).map(move |x| (x, y)))
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (Position, &T)> {
+        self.positions().zip(self.cells.iter())
+    }
+
+    fn offset_positions(
+        &self,
+        (x, y): Position,
+        offsets: &'static [(isize, isize)],
+    ) -> impl Iterator<Item = Position> + use<T> {
+        let (width, height) = (self.width, self.height);
+        offsets.iter().filter_map(move |&(dx, dy)| {
+            let neighbour = (x.checked_add_signed(dx)?, y.checked_add_signed(dy)?);
+            (neighbour.0 < width && neighbour.1 < height).then_some(neighbour)
+        })
+    }
+
+    /// The positions above, right of, below and left of `position` that are inside the grid.
+    #[cfg_attr(not(test), allow(dead_code))]
+    pub fn neighbours_4(&self, position: Position) -> impl Iterator<Item = Position> + use<T> {
+        self.offset_positions(position, &NEIGHBOUR_OFFSETS_4)
+    }
+
+    /// The positions around `position`, diagonals included, that are inside the grid.
+    pub fn neighbours_8(&self, position: Position) -> impl Iterator<Item = Position> + use<T> {
+        self.offset_positions(position, &NEIGHBOUR_OFFSETS_8)
+    }
+
+    /// The position of the first cell, row by row, matching `predicate`.
+    pub fn find(&self, predicate: impl Fn(&T) -> bool) -> Option<Position> {
+        self.iter()
+            .find(|(_, cell)| predicate(cell))
+            .map(|(position, _)| position)
+    }
+
+    /// The positions of all cells matching `predicate`, row by row.
+    pub fn find_all(&self, predicate: impl Fn(&T) -> bool) -> impl Iterator<Item = Position> {
+        self.iter()
+            .filter(move |(_, cell)| predicate(cell))
+            .map(|(position, _)| position)
+    }
+
+    /// Displays the grid with one character per cell, the inverse of [`Grid::parse`].
+    pub fn display<F: Fn(&T) -> char>(&self, to_char: F) -> Display<'_, T, F> {
+        Display {
+            grid: self,
+            to_char,
+        }
+    }
+}
+
+impl<T> Index<Position> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, (x, y): Position) -> &T {
+        assert!(self.contains((x, y)), "({}, {}) is outside the grid", x, y);
+        &self.cells[y * self.width + x]
+    }
+}
+
+impl<T> IndexMut<Position> for Grid<T> {
+    fn index_mut(&mut self, (x, y): Position) -> &mut T {
+        assert!(self.contains((x, y)), "({}, {}) is outside the grid", x, y);
+        &mut self.cells[y * self.width + x]
+    }
+}
+
+pub struct Display<'g, T, F> {
+    grid: &'g Grid<T>,
+    to_char: F,
+}
+
+impl<T, F: Fn(&T) -> char> fmt::Display for Display<'_, T, F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for row in self.grid.rows() {
+            let row = row.iter().map(&self.to_char).collect::<String>();
+            writeln!(f, "{}", row)?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for Grid<char> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.display(|&c| c).fmt(f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::text::Text;
+
+    fn parse_cell(c: char) -> Option<bool> {
+        match c {
+            '#' => Some(true),
+            '.' => Some(false),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn test_grid_parse() {
+        let grid = Text::new("#..\n.##\n")
+            .grid("`#` or `.`", parse_cell)
+            .unwrap();
+        assert_eq!((grid.width(), grid.height()), (3, 2));
+        assert_eq!(grid.row(1), [false, true, true]);
+        assert_eq!(grid.column(1).collect::<Vec<_>>(), [&false, &true]);
+        assert_eq!(grid.get((2, 1)), Some(&true));
+        assert_eq!(grid.get((3, 1)), None);
+        assert_eq!(grid.find(|&cell| !cell), Some((1, 0)));
+        assert_eq!(
+            grid.find_all(|&cell| cell).collect::<Vec<_>>(),
+            [(0, 0), (1, 1), (2, 1)]
+        );
+        assert_eq!(
+            grid.display(|&cell| if cell { '#' } else { '.' })
+                .to_string(),
+            "#..\n.##\n"
+        );
+        let error = Text::new("#.\n.x\n")
+            .grid("`#` or `.`", parse_cell)
+            .unwrap_err();
+        assert_eq!((error.line, error.column), (2, 2));
+        assert_eq!(error.message, "expected `#` or `.`");
+        let error = Text::new("#.\n.\n")
+            .grid("`#` or `.`", parse_cell)
+            .unwrap_err();
+        assert_eq!(error.message, "expected a row of 2 cells");
+    }
+
+    #[test]
+    fn test_grid_neighbours() {
+        let grid = Grid::new(3, 2, '.');
+        assert_eq!(
+            grid.neighbours_4((0, 0)).collect::<Vec<_>>(),
+            [(1, 0), (0, 1)]
+        );
+        assert_eq!(grid.neighbours_8((1, 1)).count(), 5);
+        assert_eq!(grid.neighbours_8((1, 0)).count(), 5);
+        assert_eq!(grid.to_string(), "...\n...\n");
+    }
+}
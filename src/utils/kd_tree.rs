@@ -0,0 +1,203 @@
+use crate::utils::metric::Metric;
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+
+pub type Point<const D: usize> = [u64; D];
+
+/// The number of neighbours first fetched for each point by [`KdTree::pairs_by_distance`].
+const INITIAL_NEIGHBOURS: usize = 4;
+
+/// A k-d tree over points with `D` coordinates, for nearest neighbour queries by `metric`.
+pub struct KdTree<const D: usize> {
+    points: Vec<Point<D>>,
+    metric: Metric,
+    /// Indices into `points`, arranged so the root of every subtree is the middle of its range,
+    /// with the smaller coordinates on its axis before it and the larger ones after.
+    nodes: Vec<usize>,
+}
+
+impl<const D: usize> KdTree<D> {
+    pub fn new(points: Vec<Point<D>>, metric: Metric) -> Self {
+        let mut nodes = (0..points.len()).collect::<Vec<_>>();
+        Self::build(&points, &mut nodes, 0);
+        Self {
+            points,
+            metric,
+            nodes,
+        }
+    }
+
+    fn build(points: &[Point<D>], nodes: &mut [usize], depth: usize) {
+        if nodes.len() <= 1 {
+            return;
+        }
+        let middle = nodes.len() / 2;
+        nodes.select_nth_unstable_by_key(middle, |&index| points[index][depth % D]);
+        let (left, right) = nodes.split_at_mut(middle);
+        Self::build(points, left, depth + 1);
+        Self::build(points, &mut right[1..], depth + 1);
+    }
+
+    /// The `k` points closest to `points[target]` that `include` accepts, as `(distance, index)`
+    /// ordered by distance and then by index. The target itself is never included.
+    pub fn nearest(
+        &self,
+        target: usize,
+        k: usize,
+        include: impl Fn(usize) -> bool,
+    ) -> Vec<(u64, usize)> {
+        let mut nearest = BinaryHeap::with_capacity(k + 1);
+        if k > 0 {
+            self.search(&self.nodes, 0, target, k, &include, &mut nearest);
+        }
+        nearest.into_sorted_vec()
+    }
+
+    fn search(
+        &self,
+        nodes: &[usize],
+        depth: usize,
+        target: usize,
+        k: usize,
+        include: &impl Fn(usize) -> bool,
+        nearest: &mut BinaryHeap<(u64, usize)>,
+    ) {
+        if nodes.is_empty() {
+            return;
+        }
+        let middle = nodes.len() / 2;
+        let index = nodes[middle];
+        let (point, target_point) = (&self.points[index], &self.points[target]);
+        if index != target && include(index) {
+            let candidate = (self.metric.distance(point, target_point), index);
+            if nearest.len() < k {
+                nearest.push(candidate);
+            } else if nearest.peek().is_some_and(|&farthest| candidate < farthest) {
+                nearest.pop();
+                nearest.push(candidate);
+            }
+        }
+        let axis = depth % D;
+        let (near, far) = if target_point[axis] < point[axis] {
+            (&nodes[..middle], &nodes[middle + 1..])
+        } else {
+            (&nodes[middle + 1..], &nodes[..middle])
+        };
+        self.search(near, depth + 1, target, k, include, nearest);
+        // Everything on the far side is at least as far away as the splitting plane.
+        let plane_distance = self
+            .metric
+            .axis_distance(target_point[axis].abs_diff(point[axis]));
+        if nearest.len() < k
+            || nearest
+                .peek()
+                .is_some_and(|&(farthest, _)| plane_distance <= farthest)
+        {
+            self.search(far, depth + 1, target, k, include, nearest);
+        }
+    }
+
+    /// Iterates over all pairs of points as `(distance, index, index)`, ordered by
+    /// distance and then by indices, without computing the distance of every pair up front.
+    pub fn pairs_by_distance(&self) -> PairsByDistance<'_, D> {
+        let mut pairs = PairsByDistance {
+            tree: self,
+            neighbours: vec![Vec::new(); self.points.len()],
+            yielded: vec![0; self.points.len()],
+            exhausted: vec![false; self.points.len()],
+            next_pairs: BinaryHeap::new(),
+        };
+        for index in 0..self.points.len() {
+            pairs.queue_next_pair(index);
+        }
+        pairs
+    }
+}
+
+/// The iterator returned by [`KdTree::pairs_by_distance`].
+///
+/// Every point keeps a buffer of its nearest neighbours with a higher index, which is refilled
+/// with twice as many neighbours whenever it runs out.
+pub struct PairsByDistance<'t, const D: usize> {
+    tree: &'t KdTree<D>,
+    /// The fetched neighbours of each point that are not yet yielded, nearest last.
+    neighbours: Vec<Vec<(u64, usize)>>,
+    yielded: Vec<usize>,
+    /// Whether all neighbours of each point have been fetched.
+    exhausted: Vec<bool>,
+    /// The next pair of every point that has one left.
+    next_pairs: BinaryHeap<Reverse<(u64, usize, usize)>>,
+}
+
+impl<const D: usize> PairsByDistance<'_, D> {
+    fn queue_next_pair(&mut self, index: usize) {
+        if self.neighbours[index].is_empty() && !self.exhausted[index] {
+            let k = (2 * self.yielded[index]).max(INITIAL_NEIGHBOURS);
+            let nearest = self.tree.nearest(index, k, |other| other > index);
+            self.exhausted[index] = nearest.len() < k;
+            self.neighbours[index] = nearest[self.yielded[index]..]
+                .iter()
+                .rev()
+                .copied()
+                .collect();
+        }
+        if let Some((distance, other)) = self.neighbours[index].pop() {
+            self.next_pairs.push(Reverse((distance, index, other)));
+        }
+    }
+}
+
+impl<const D: usize> Iterator for PairsByDistance<'_, D> {
+    type Item = (u64, usize, usize);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let Reverse(pair) = self.next_pairs.pop()?;
+        self.yielded[pair.1] += 1;
+        self.queue_next_pair(pair.1);
+        Some(pair)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::random::Random;
+
+    /// Pseudo-random points with many repeated coordinates.
+    fn generate_points(count: usize) -> Vec<Point<3>> {
+        let mut random = Random::new(12345);
+        (0..count)
+            .map(|_| [random.below(50), random.below(50), random.below(50)])
+            .collect()
+    }
+
+    #[test]
+    fn test_pairs_by_distance_matches_sorting_all_pairs() {
+        let points = generate_points(300);
+        for metric in [
+            Metric::SquaredEuclidean,
+            Metric::Manhattan,
+            Metric::Chebyshev,
+        ] {
+            let mut all_pairs = Vec::new();
+            for i in 0..points.len() {
+                for j in i + 1..points.len() {
+                    all_pairs.push((metric.distance(&points[i], &points[j]), i, j));
+                }
+            }
+            all_pairs.sort();
+            let tree = KdTree::new(points.clone(), metric);
+            assert_eq!(tree.pairs_by_distance().collect::<Vec<_>>(), all_pairs);
+        }
+    }
+
+    #[test]
+    fn test_nearest() {
+        let tree = KdTree::new(
+            vec![[0, 0], [5, 5], [1, 0], [0, 2], [1, 1]],
+            Metric::SquaredEuclidean,
+        );
+        assert_eq!(tree.nearest(0, 3, |_| true), [(1, 2), (2, 4), (4, 3)]);
+        assert_eq!(tree.nearest(0, 9, |index| index > 2).len(), 2);
+    }
+}
@@ -0,0 +1,57 @@
+use std::str::FromStr;
+
+/// A way of measuring the distance between points with integer coordinates.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum Metric {
+    /// The squared straight-line distance, which orders points like the distance itself.
+    #[default]
+    SquaredEuclidean,
+    Manhattan,
+    Chebyshev,
+}
+
+impl Metric {
+    pub fn distance<const D: usize>(self, a: &[u64; D], b: &[u64; D]) -> u64 {
+        let differences = a.iter().zip(b).map(|(a, b)| a.abs_diff(*b));
+        match self {
+            Metric::SquaredEuclidean => differences.map(|difference| difference.pow(2)).sum(),
+            Metric::Manhattan => differences.sum(),
+            Metric::Chebyshev => differences.max().unwrap_or(0),
+        }
+    }
+
+    /// The smallest distance between points whose coordinates on one axis differ by `difference`.
+    pub fn axis_distance(self, difference: u64) -> u64 {
+        match self {
+            Metric::SquaredEuclidean => difference.pow(2),
+            Metric::Manhattan | Metric::Chebyshev => difference,
+        }
+    }
+}
+
+impl FromStr for Metric {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "euclidean" => Ok(Metric::SquaredEuclidean),
+            "manhattan" => Ok(Metric::Manhattan),
+            "chebyshev" => Ok(Metric::Chebyshev),
+            _ => Err(format!("unknown metric `{}`", value)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_metric_distance() {
+        let (a, b) = ([1, 10, 4], [4, 6, 4]);
+        assert_eq!(Metric::SquaredEuclidean.distance(&a, &b), 25);
+        assert_eq!(Metric::Manhattan.distance(&a, &b), 7);
+        assert_eq!(Metric::Chebyshev.distance(&a, &b), 4);
+        assert_eq!("chebyshev".parse(), Ok(Metric::Chebyshev));
+    }
+}
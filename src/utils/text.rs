@@ -0,0 +1,87 @@
+use crate::error::ParseError;
+use crate::utils::grid::Grid;
+
+/// A line of the input together with its 0-based index in the original input.
+pub type NumberedLine<'t> = (usize, &'t str);
+
+/// A puzzle input with normalised line endings and no trailing whitespace.
+///
+/// Lines keep their index in the original input, so parse errors still point at the right line.
+pub struct Text<'t> {
+    /// The lines from the first to the last non-blank one, blank lines included.
+    lines: Vec<NumberedLine<'t>>,
+    line_count: usize,
+}
+
+impl<'t> Text<'t> {
+    pub fn new(input: &'t str) -> Self {
+        let lines = input
+            .lines()
+            .map(str::trim_end)
+            .enumerate()
+            .collect::<Vec<_>>();
+        let line_count = lines.len();
+        let start = lines
+            .iter()
+            .position(|(_, line)| !line.is_empty())
+            .unwrap_or(line_count);
+        let end = lines
+            .iter()
+            .rposition(|(_, line)| !line.is_empty())
+            .map_or(start, |end| end + 1);
+        Self {
+            lines: lines[start..end].to_vec(),
+            line_count,
+        }
+    }
+
+    /// The number of lines in the original input, for errors about something missing at its end.
+    pub fn line_count(&self) -> usize {
+        self.line_count
+    }
+
+    /// The non-blank lines.
+    pub fn lines(&self) -> impl Iterator<Item = NumberedLine<'t>> + '_ {
+        self.lines
+            .iter()
+            .copied()
+            .filter(|(_, line)| !line.is_empty())
+    }
+
+    /// The groups of non-blank lines, separated by one or more blank lines.
+    pub fn blocks(&self) -> Vec<&[NumberedLine<'t>]> {
+        self.lines
+            .split(|(_, line)| line.is_empty())
+            .filter(|block| !block.is_empty())
+            .collect()
+    }
+
+    /// Parses the non-blank lines as a grid, see [`Grid::parse`].
+    pub fn grid<T>(
+        &self,
+        expected: &str,
+        parse_cell: impl Fn(char) -> Option<T>,
+    ) -> Result<Grid<T>, ParseError> {
+        Grid::parse(&self.lines().collect::<Vec<_>>(), expected, parse_cell)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_text_normalises_the_input() {
+        let text = Text::new("\r\n1-3  \r\n5-8\r\n \r\n\r\n17\t\r\n\r\n");
+        assert_eq!(text.line_count(), 7);
+        assert_eq!(
+            text.lines().collect::<Vec<_>>(),
+            vec![(1, "1-3"), (2, "5-8"), (5, "17")]
+        );
+        assert_eq!(
+            text.blocks(),
+            vec![&[(1, "1-3"), (2, "5-8")][..], &[(5, "17")][..]]
+        );
+        assert!(Text::new("\n \n").blocks().is_empty());
+    }
+}